@@ -1,8 +1,15 @@
-use soroban_sdk::{Address, Env, token};
+use soroban_sdk::{xdr::ToXdr, Address, BytesN, Env, Vec, token};
 use crate::storage;
-use crate::types::{BookingRecord, BookingStatus};
-use crate::error::VaultError;
+use crate::types::{
+    AuctionRecord, BillingPolicy, BookingFunding, BookingLimits, BookingRecord, BookingStatus,
+    BountyAward, BountyRecord, BountySubmission, BudgetPool, CounterProposal, DurationReport,
+    EngagementRecord, OptionalRejectionReason, OracleQuorum, PoolBeneficiary, PricingTier,
+    QuestionRecord, Quote, RejectionReason, RescheduleProposal, RescheduleRecord, RetainerRecord,
+    RoundingMode, SettlementQuote, SolvencyReport, VoucherCampaign, VoucherDiscount,
+};
+use crate::error::{MarketError, VaultError};
 use crate::events;
+use crate::registry::IdentityRegistryClient;
 
 pub fn initialize_vault(
     env: &Env,
@@ -23,47 +30,423 @@ pub fn initialize_vault(
     Ok(())
 }
 
+/// Maximum number of tiers in a booking's pricing schedule
+const MAX_PRICING_TIERS: u32 = 8;
+
+/// 7 days in seconds
+const MAX_GRACE_PERIOD: u64 = 604_800;
+
+/// 1 day in seconds; upper bound for a billing increment or minimum charge
+const MAX_BILLING_DURATION: u64 = 86_400;
+
+/// 100% in basis points
+const BPS_DENOMINATOR: i128 = 10_000;
+
+/// Unused retainer time carries over for at most this many periods' allotments
+const MAX_ROLLOVER_PERIODS: u64 = 2;
+
+/// Maximum number of milestones in an engagement
+const MAX_MILESTONES: u32 = 10;
+
+/// 30 days in seconds
+const MAX_APPROVAL_TIMEOUT: u64 = 2_592_000;
+
+/// Maximum number of answers a bounty accepts
+const MAX_BOUNTY_SUBMISSIONS: u32 = 50;
+
+/// 7 days in seconds; how long a poster has to award a bounty after it closes
+const BOUNTY_AWARD_WINDOW: u64 = 604_800;
+
+/// Maximum number of bounty submissions returned per page
+const MAX_PAGE_SIZE: u32 = 20;
+
+#[allow(clippy::too_many_arguments)]
 pub fn book_session(
     env: &Env,
     user: &Address,
     expert: &Address,
     rate_per_second: i128,
     max_duration: u64,
+    scheduled_start: u64,
+    grace_period: u64,
+    voucher_id: Option<u64>,
 ) -> Result<u64, VaultError> {
-    // Require authorization from the user creating the booking
-    user.require_auth();
+    let pricing = flat_pricing(env, rate_per_second)?;
+
+    create_booking(
+        env,
+        user,
+        expert,
+        pricing,
+        max_duration,
+        scheduled_start,
+        grace_period,
+        voucher_id,
+        DepositSource::Transfer,
+        false,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn book_session_for(
+    env: &Env,
+    user: &Address,
+    expert: &Address,
+    rate_per_second: i128,
+    max_duration: u64,
+    scheduled_start: u64,
+    grace_period: u64,
+    voucher_id: Option<u64>,
+) -> Result<u64, VaultError> {
+    let pricing = flat_pricing(env, rate_per_second)?;
+
+    create_booking(
+        env,
+        user,
+        expert,
+        pricing,
+        max_duration,
+        scheduled_start,
+        grace_period,
+        voucher_id,
+        DepositSource::Allowance,
+        false,
+    )
+}
 
+/// A flat rate is a single tier starting at zero
+fn flat_pricing(env: &Env, rate_per_second: i128) -> Result<Vec<PricingTier>, VaultError> {
     // Validate rate
     if rate_per_second <= 0 {
         return Err(VaultError::InvalidAmount);
     }
 
+    Ok(Vec::from_array(
+        env,
+        [PricingTier {
+            threshold: 0,
+            rate_per_second,
+        }],
+    ))
+}
+
+pub fn register_signing_key(
+    env: &Env,
+    expert: &Address,
+    public_key: BytesN<32>,
+) -> Result<(), VaultError> {
+    // Require expert authorization
+    expert.require_auth();
+
+    storage::set_signing_key(env, expert, &public_key);
+    events::signing_key_set(env, expert, &public_key);
+
+    Ok(())
+}
+
+pub fn book_with_quote(
+    env: &Env,
+    user: &Address,
+    quote: Quote,
+    signature: BytesN<64>,
+    scheduled_start: u64,
+    grace_period: u64,
+) -> Result<u64, VaultError> {
+    // 1. The quote is addressed to a single user
+    if quote.user != *user {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    // 2. Validate expiry and nonce
+    if env.ledger().timestamp() > quote.expires_at {
+        return Err(VaultError::QuoteExpired);
+    }
+
+    if storage::is_quote_nonce_used(env, &quote.expert, quote.nonce) {
+        return Err(VaultError::QuoteAlreadyUsed);
+    }
+
+    if quote.rate_per_second <= 0 {
+        return Err(VaultError::InvalidAmount);
+    }
+
+    // 3. Verify the expert's signature over this vault and the quote; traps if invalid
+    let public_key = storage::get_signing_key(env, &quote.expert)
+        .ok_or(VaultError::SigningKeyNotSet)?;
+    let payload = (env.current_contract_address(), quote.clone()).to_xdr(env);
+    env.crypto().ed25519_verify(&public_key, &payload, &signature);
+
+    storage::mark_quote_nonce_used(env, &quote.expert, quote.nonce);
+
+    // 4. Book at the quoted terms
+    let pricing = Vec::from_array(
+        env,
+        [PricingTier {
+            threshold: 0,
+            rate_per_second: quote.rate_per_second,
+        }],
+    );
+    let booking_id = create_booking(
+        env,
+        user,
+        &quote.expert,
+        pricing,
+        quote.max_duration,
+        scheduled_start,
+        grace_period,
+        None,
+        DepositSource::Transfer,
+        false,
+    )?;
+
+    events::quote_redeemed(env, booking_id, user, &quote.expert, quote.nonce);
+
+    Ok(booking_id)
+}
+
+pub fn book_session_tiered(
+    env: &Env,
+    user: &Address,
+    expert: &Address,
+    pricing: Vec<PricingTier>,
+    max_duration: u64,
+    scheduled_start: u64,
+    grace_period: u64,
+) -> Result<u64, VaultError> {
+    validate_pricing(&pricing)?;
+
+    create_booking(
+        env,
+        user,
+        expert,
+        pricing,
+        max_duration,
+        scheduled_start,
+        grace_period,
+        None,
+        DepositSource::Transfer,
+        false,
+    )
+}
+
+/// How the user's share of a booking deposit reaches the vault
+enum DepositSource {
+    Transfer,  // Direct transfer authorized by the user in this invocation
+    Allowance, // transfer_from against an allowance the user granted the vault
+    Pool(u64), // Drawn from a budget pool the user is a beneficiary of
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_booking(
+    env: &Env,
+    user: &Address,
+    expert: &Address,
+    pricing: Vec<PricingTier>,
+    max_duration: u64,
+    scheduled_start: u64,
+    grace_period: u64,
+    voucher_id: Option<u64>,
+    source: DepositSource,
+    stream: bool,
+) -> Result<u64, VaultError> {
+    // Require authorization from the user creating the booking
+    user.require_auth();
+
+    // The expert must be taking bookings, and from this user
+    check_expert_available(env, user, expert)?;
+
+    // Validate the start window against the ledger time
+    let expires_at = booking_window(env, scheduled_start, max_duration, grace_period)?;
+
     // Calculate total deposit
-    let total_deposit = rate_per_second * (max_duration as i128);
+    let total_deposit = pricing_cost(&pricing, max_duration)?;
 
     if total_deposit <= 0 {
         return Err(VaultError::InvalidAmount);
     }
 
+    // Enforce the anti-spam limits
+    check_booking_limits(env, user, expert, total_deposit)?;
+
+    // Apply the voucher, if any; the campaign covers the subsidy
+    let subsidy = match voucher_id {
+        Some(campaign_id) => redeem_voucher(env, campaign_id, user, total_deposit)?,
+        None => 0,
+    };
+
     // Get the token contract
     let token_address = storage::get_token(env);
     let token_client = token::Client::new(env, &token_address);
 
-    // Transfer tokens from user to this contract
+    // Transfer the user's share from user to this contract
     let contract_address = env.current_contract_address();
-    token_client.transfer(user, &contract_address, &total_deposit);
+    let user_share = total_deposit - subsidy;
+    let mut pool_id = None;
+    if user_share > 0 {
+        match source {
+            DepositSource::Transfer => {
+                token_client.transfer(user, &contract_address, &user_share);
+            }
+            DepositSource::Allowance => {
+                if token_client.allowance(user, &contract_address) < user_share {
+                    return Err(VaultError::InsufficientAllowance);
+                }
+                token_client.transfer_from(&contract_address, user, &contract_address, &user_share);
+            }
+            DepositSource::Pool(id) => {
+                draw_from_pool(env, id, user, expert, user_share)?;
+                pool_id = Some(id);
+            }
+        }
+    }
+
+    // Voucher and pool funds are already locked; only tokens from the user are new
+    if pool_id.is_none() {
+        storage::adjust_locked(env, user_share);
+    }
+
+    let booking_id = record_booking(
+        env,
+        user,
+        expert,
+        pricing,
+        max_duration,
+        total_deposit,
+        scheduled_start,
+        expires_at,
+        BookingFunding {
+            voucher_id,
+            subsidy,
+            pool_id,
+        },
+        stream,
+    );
+
+    if let Some(campaign_id) = voucher_id {
+        events::voucher_redeemed(env, campaign_id, booking_id, user, expert, subsidy);
+    }
+
+    Ok(booking_id)
+}
+
+/// The expert must be taking bookings and must not have blocked the user
+fn check_expert_available(env: &Env, user: &Address, expert: &Address) -> Result<(), VaultError> {
+    if !storage::is_accepting_bookings(env, expert) {
+        return Err(VaultError::NotAcceptingBookings);
+    }
+
+    if storage::is_user_blocked(env, expert, user) {
+        return Err(VaultError::UserBlocked);
+    }
 
+    Ok(())
+}
+
+/// Rejects deposits below the minimum and users already at their open booking caps
+fn check_booking_limits(
+    env: &Env,
+    user: &Address,
+    expert: &Address,
+    total_deposit: i128,
+) -> Result<(), VaultError> {
+    let limits = storage::get_booking_limits(env);
+
+    if total_deposit < limits.min_deposit {
+        return Err(VaultError::DepositBelowMinimum);
+    }
+
+    if limits.max_pending_per_user > 0
+        && storage::get_open_bookings(env, user) >= limits.max_pending_per_user
+    {
+        return Err(VaultError::TooManyPendingBookings);
+    }
+
+    if limits.max_pending_per_pair > 0
+        && storage::get_open_pair_bookings(env, user, expert) >= limits.max_pending_per_pair
+    {
+        return Err(VaultError::TooManyPendingBookings);
+    }
+
+    Ok(())
+}
+
+/// Reports a failed booking check from a market function under the same code
+fn market_error(error: VaultError) -> MarketError {
+    match error {
+        VaultError::NotAcceptingBookings => MarketError::NotAcceptingBookings,
+        VaultError::UserBlocked => MarketError::UserBlocked,
+        VaultError::TooManyPendingBookings => MarketError::TooManyPendingBookings,
+        VaultError::DepositBelowMinimum => MarketError::DepositBelowMinimum,
+        _ => MarketError::InvalidAmount,
+    }
+}
+
+/// Moves a booking to a final status and releases it from the user's open booking counts
+fn close_booking(env: &Env, booking: &BookingRecord, status: BookingStatus) {
+    storage::update_booking_status(env, booking.id, status);
+    release_booking(env, booking);
+}
+
+/// Drops a closed booking from the open booking counts along with its pending proposals
+fn release_booking(env: &Env, booking: &BookingRecord) {
+    storage::decrement_open_bookings(env, &booking.user, &booking.expert);
+    storage::remove_counter_proposal(env, booking.id);
+    storage::remove_reschedule_proposal(env, booking.id);
+}
+
+/// Validates the scheduled start and returns when the booking's finalization window closes
+fn booking_window(
+    env: &Env,
+    scheduled_start: u64,
+    max_duration: u64,
+    grace_period: u64,
+) -> Result<u64, VaultError> {
+    if scheduled_start < env.ledger().timestamp() || grace_period > MAX_GRACE_PERIOD {
+        return Err(VaultError::InvalidSchedule);
+    }
+
+    scheduled_start
+        .checked_add(max_duration)
+        .and_then(|end| end.checked_add(grace_period))
+        .ok_or(VaultError::InvalidSchedule)
+}
+
+/// Stores a Pending booking for a deposit already held by the vault and indexes it
+#[allow(clippy::too_many_arguments)]
+fn record_booking(
+    env: &Env,
+    user: &Address,
+    expert: &Address,
+    pricing: Vec<PricingTier>,
+    max_duration: u64,
+    total_deposit: i128,
+    scheduled_start: u64,
+    expires_at: u64,
+    funding: BookingFunding,
+    stream: bool,
+) -> u64 {
     // Generate booking ID and create booking
     let booking_id = storage::get_next_booking_id(env);
     let booking = BookingRecord {
         id: booking_id,
         user: user.clone(),
         expert: expert.clone(),
-        rate_per_second,
+        rate_per_second: pricing.get_unchecked(0).rate_per_second,
+        pricing,
         max_duration,
         total_deposit,
         status: BookingStatus::Pending,
         created_at: env.ledger().timestamp(),
+        scheduled_start,
+        expires_at,
+        billing: storage::get_billing_policy(env, expert),
+        funding,
+        total_tips: 0,
+        stream,
+        withdrawn: 0,
+        stopped_at: None,
+        rejection_reason: OptionalRejectionReason::None,
+        rejection_hash: None,
     };
 
     // Save booking
@@ -72,11 +455,58 @@ pub fn book_session(
     // Add booking to user and expert lists
     storage::add_booking_to_user_list(env, user, booking_id);
     storage::add_booking_to_expert_list(env, expert, booking_id);
+    storage::increment_open_bookings(env, user, expert);
 
     // Emit event for booking creation
     events::booking_created(env, booking_id, user, expert, total_deposit);
 
-    Ok(booking_id)
+    booking_id
+}
+
+/// Tiers must start at zero, have strictly increasing thresholds and positive rates
+fn validate_pricing(pricing: &Vec<PricingTier>) -> Result<(), VaultError> {
+    if pricing.is_empty() || pricing.len() > MAX_PRICING_TIERS {
+        return Err(VaultError::InvalidPricingSchedule);
+    }
+
+    let mut previous: Option<u64> = None;
+    for tier in pricing.iter() {
+        let ordered = match previous {
+            None => tier.threshold == 0,
+            Some(threshold) => tier.threshold > threshold,
+        };
+        if !ordered || tier.rate_per_second <= 0 {
+            return Err(VaultError::InvalidPricingSchedule);
+        }
+        previous = Some(tier.threshold);
+    }
+
+    Ok(())
+}
+
+/// Integrates the pricing schedule over the first `duration` seconds
+fn pricing_cost(pricing: &Vec<PricingTier>, duration: u64) -> Result<i128, VaultError> {
+    let mut total: i128 = 0;
+
+    for (i, tier) in pricing.iter().enumerate() {
+        if duration <= tier.threshold {
+            break;
+        }
+
+        let tier_end = match pricing.get(i as u32 + 1) {
+            Some(next) => next.threshold.min(duration),
+            None => duration,
+        };
+        let seconds = (tier_end - tier.threshold) as i128;
+
+        total = tier
+            .rate_per_second
+            .checked_mul(seconds)
+            .and_then(|cost| total.checked_add(cost))
+            .ok_or(VaultError::InvalidAmount)?;
+    }
+
+    Ok(total)
 }
 
 pub fn finalize_session(
@@ -97,119 +527,2598 @@ pub fn finalize_session(
         return Err(VaultError::BookingNotPending);
     }
 
-    // 4. Calculate payments
-    let expert_pay = booking.rate_per_second * (actual_duration as i128);
-    let refund = booking.total_deposit - expert_pay;
+    // Streams settle through withdraw_accrued and stop_stream
+    if booking.stream {
+        return Err(VaultError::InvalidBookingMode);
+    }
 
-    // Ensure calculations are valid
-    if expert_pay < 0 || refund < 0 {
-        return Err(VaultError::InvalidAmount);
+    // 4. Only settle within the booking's start window
+    check_finalization_window(env, &booking)?;
+
+    // 5. High-value bookings must be settled by the oracle quorum
+    if requires_quorum(env, &booking) {
+        return Err(VaultError::QuorumRequired);
+    }
+
+    settle_booking(env, &booking, actual_duration)
+}
+
+/// Finalization is accepted from the scheduled start until the booking expires
+fn check_finalization_window(env: &Env, booking: &BookingRecord) -> Result<(), VaultError> {
+    let now = env.ledger().timestamp();
+
+    if now < booking.scheduled_start {
+        return Err(VaultError::SessionNotStarted);
+    }
+
+    if now > booking.expires_at {
+        return Err(VaultError::BookingExpired);
     }
 
-    // 5. Get token contract
+    Ok(())
+}
+
+/// Pays the expert for `actual_duration`, refunds the rest and marks the booking Complete
+fn settle_booking(
+    env: &Env,
+    booking: &BookingRecord,
+    actual_duration: u64,
+) -> Result<(), VaultError> {
+    // 1. Calculate payments
+    let quote = calculate_settlement(env, booking, actual_duration)?;
+    let expert_pay = quote.expert_pay;
+    let refund = quote.refund;
+
+    // 2. Get token contract
     let token_address = storage::get_token(env);
     let token_client = token::Client::new(env, &token_address);
     let contract_address = env.current_contract_address();
 
-    // 6. Execute transfers
+    // 3. Execute transfers
     // Pay expert
     if expert_pay > 0 {
         token_client.transfer(&contract_address, &booking.expert, &expert_pay);
     }
 
     // Refund user
-    if refund > 0 {
-        token_client.transfer(&contract_address, &booking.user, &refund);
+    refund_booking(env, booking, refund)?;
+
+    // Retain the platform fee
+    if quote.platform_fee > 0 {
+        storage::set_fee_pool(env, storage::get_fee_pool(env) + quote.platform_fee);
     }
+    storage::adjust_locked(env, -(expert_pay + quote.platform_fee));
 
-    // 7. Update booking status to Complete
-    storage::update_booking_status(env, booking_id, BookingStatus::Complete);
+    // 4. Update booking status to Complete
+    close_booking(env, booking, BookingStatus::Complete);
+    record_completed_session(
+        env,
+        booking,
+        quote.billable_duration,
+        expert_pay + quote.platform_fee,
+    );
 
-    // 8. Emit SessionFinalized event
-    events::session_finalized(env, booking_id, actual_duration, expert_pay);
+    // 5. Emit SessionFinalized event
+    events::session_finalized(env, booking, actual_duration, &quote);
 
     Ok(())
 }
 
-/// 24 hours in seconds
-const RECLAIM_TIMEOUT: u64 = 86400;
+/// Credits a completed session to the running stats of both parties
+fn record_completed_session(env: &Env, booking: &BookingRecord, seconds: u64, cost: i128) {
+    let token = storage::get_token(env);
 
-pub fn reclaim_stale_session(
-    env: &Env,
-    user: &Address,
-    booking_id: u64,
-) -> Result<(), VaultError> {
-    // 1. Require user authorization
-    user.require_auth();
+    let mut expert_stats = storage::get_expert_stats(env, &booking.expert);
+    expert_stats.completed_sessions += 1;
+    expert_stats.seconds_billed += seconds;
+    let earned = expert_stats.gross_earnings.get(token.clone()).unwrap_or(0);
+    expert_stats.gross_earnings.set(token.clone(), earned + cost);
+    storage::save_expert_stats(env, &booking.expert, &expert_stats);
 
-    // 2. Get booking and verify it exists
-    let booking = storage::get_booking(env, booking_id)
-        .ok_or(VaultError::BookingNotFound)?;
+    let mut user_stats = storage::get_user_stats(env, &booking.user);
+    user_stats.sessions += 1;
+    let spent = user_stats.total_spent.get(token.clone()).unwrap_or(0);
+    user_stats.total_spent.set(token, spent + cost);
+    storage::save_user_stats(env, &booking.user, &user_stats);
+}
 
-    // 3. Verify the caller is the booking owner
-    if booking.user != *user {
-        return Err(VaultError::NotAuthorized);
-    }
+/// Counts a stale booking refunded by reclaim or expiry against the expert
+fn record_reclaim(env: &Env, expert: &Address) {
+    let mut stats = storage::get_expert_stats(env, expert);
+    stats.reclaims += 1;
+    storage::save_expert_stats(env, expert, &stats);
+}
 
-    // 4. Verify booking is in Pending status
-    if booking.status != BookingStatus::Pending {
-        return Err(VaultError::BookingNotPending);
+/// Applies the booking's billing policy to the actual duration and splits the deposit
+fn calculate_settlement(
+    env: &Env,
+    booking: &BookingRecord,
+    actual_duration: u64,
+) -> Result<SettlementQuote, VaultError> {
+    if actual_duration > booking.max_duration {
+        return Err(VaultError::InvalidAmount);
     }
 
-    // 5. Check if 24 hours have passed since booking creation
-    let current_time = env.ledger().timestamp();
-    if current_time <= booking.created_at + RECLAIM_TIMEOUT {
-        return Err(VaultError::ReclaimTooEarly);
+    let billable_duration =
+        billable_duration(&booking.billing, actual_duration, booking.max_duration)?;
+    let total_cost = pricing_cost(&booking.pricing, billable_duration)?;
+    let refund = booking.total_deposit - total_cost;
+
+    // Ensure calculations are valid
+    if total_cost < 0 || refund < 0 {
+        return Err(VaultError::InvalidAmount);
     }
 
-    // 6. Transfer total_deposit back to user
-    let token_address = storage::get_token(env);
-    let token_client = token::Client::new(env, &token_address);
-    let contract_address = env.current_contract_address();
-    token_client.transfer(&contract_address, &booking.user, &booking.total_deposit);
+    let platform_fee = platform_fee(env, total_cost)?;
 
-    // 7. Update booking status to Reclaimed
-    storage::update_booking_status(env, booking_id, BookingStatus::Reclaimed);
+    Ok(SettlementQuote {
+        billable_duration,
+        expert_pay: total_cost - platform_fee,
+        platform_fee,
+        refund,
+    })
+}
 
-    // 8. Emit event
-    events::session_reclaimed(env, booking_id, booking.total_deposit);
+/// Share of a payout retained by the platform at the current fee rate
+fn platform_fee(env: &Env, amount: i128) -> Result<i128, VaultError> {
+    let fee = amount
+        .checked_mul(storage::get_platform_fee_bps(env) as i128)
+        .ok_or(VaultError::InvalidAmount)?;
 
-    Ok(())
+    Ok(fee / BPS_DENOMINATOR)
 }
 
-pub fn reject_session(
+/// Rounds to the billing increment and applies the minimum charge, capped at max_duration.
+/// A session that never took place (zero duration) is not billed.
+fn billable_duration(
+    policy: &BillingPolicy,
+    actual_duration: u64,
+    max_duration: u64,
+) -> Result<u64, VaultError> {
+    if actual_duration == 0 {
+        return Ok(0);
+    }
+
+    let increment = policy.increment;
+    let remainder = actual_duration % increment;
+    let rounded_down = actual_duration - remainder;
+    let round_up = match policy.rounding {
+        RoundingMode::Down => false,
+        RoundingMode::Up => remainder > 0,
+        RoundingMode::Nearest => remainder >= increment - remainder,
+    };
+
+    let rounded = if round_up {
+        rounded_down
+            .checked_add(increment)
+            .ok_or(VaultError::InvalidAmount)?
+    } else {
+        rounded_down
+    };
+
+    Ok(rounded.max(policy.min_duration).min(max_duration))
+}
+
+pub fn set_billing_policy(
     env: &Env,
     expert: &Address,
-    booking_id: u64,
+    policy: BillingPolicy,
 ) -> Result<(), VaultError> {
     // 1. Require expert authorization
     expert.require_auth();
 
-    // 2. Get booking and verify it exists
+    // 2. Validate the increment and minimum charge
+    if policy.increment == 0
+        || policy.increment > MAX_BILLING_DURATION
+        || policy.min_duration > MAX_BILLING_DURATION
+    {
+        return Err(VaultError::InvalidBillingPolicy);
+    }
+
+    // 3. Save policy; it applies to bookings created from now on
+    storage::set_billing_policy(env, expert, &policy);
+
+    Ok(())
+}
+
+pub fn quote_settlement(
+    env: &Env,
+    booking_id: u64,
+    actual_duration: u64,
+) -> Result<SettlementQuote, VaultError> {
     let booking = storage::get_booking(env, booking_id)
         .ok_or(VaultError::BookingNotFound)?;
 
-    // 3. Verify the caller is the expert in the booking
-    if booking.expert != *expert {
-        return Err(VaultError::NotAuthorized);
+    calculate_settlement(env, &booking, actual_duration)
+}
+
+/// Returns part of a booking's deposit to whoever funded it.
+/// A voucher campaign gets back its proportional share of the subsidy and the user (or the
+/// budget pool that paid for them) the rest; a full refund also gives the voucher redemption back.
+fn refund_booking(env: &Env, booking: &BookingRecord, refund: i128) -> Result<(), VaultError> {
+    let mut user_refund = refund;
+
+    if let Some(campaign_id) = booking.funding.voucher_id {
+        let campaign_share = refund
+            .checked_mul(booking.funding.subsidy)
+            .ok_or(VaultError::InvalidAmount)?
+            / booking.total_deposit;
+        user_refund -= campaign_share;
+
+        let mut campaign = storage::get_voucher_campaign(env, campaign_id)
+            .ok_or(VaultError::VoucherNotFound)?;
+
+        // Closed campaigns have already returned their budget to the fee pool
+        if campaign.active {
+            campaign.budget += campaign_share;
+            storage::save_voucher_campaign(env, &campaign);
+        } else if campaign_share > 0 {
+            storage::set_fee_pool(env, storage::get_fee_pool(env) + campaign_share);
+            storage::adjust_locked(env, -campaign_share);
+        }
+
+        if refund == booking.total_deposit {
+            let redemptions = storage::get_voucher_redemptions(env, campaign_id, &booking.user);
+            storage::set_voucher_redemptions(
+                env,
+                campaign_id,
+                &booking.user,
+                redemptions.saturating_sub(1),
+            );
+        }
     }
 
-    // 4. Verify booking is in Pending status
-    if booking.status != BookingStatus::Pending {
-        return Err(VaultError::BookingNotPending);
+    if let Some(pool_id) = booking.funding.pool_id {
+        return refund_to_pool(env, pool_id, booking, user_refund);
     }
 
-    // 5. Transfer total_deposit back to user
-    let token_address = storage::get_token(env);
-    let token_client = token::Client::new(env, &token_address);
-    let contract_address = env.current_contract_address();
-    token_client.transfer(&contract_address, &booking.user, &booking.total_deposit);
+    if user_refund > 0 {
+        let token_address = storage::get_token(env);
+        let token_client = token::Client::new(env, &token_address);
+        let contract_address = env.current_contract_address();
+        token_client.transfer(&contract_address, &booking.user, &user_refund);
+        storage::adjust_locked(env, -user_refund);
+    }
 
-    // 6. Update booking status to Rejected
-    storage::update_booking_status(env, booking_id, BookingStatus::Rejected);
+    Ok(())
+}
 
-    // 7. Emit event
-    events::session_rejected(env, booking_id, "Expert declined session");
+/// Checks the campaign and the user's redemption count, and takes the subsidy from its budget
+fn redeem_voucher(
+    env: &Env,
+    campaign_id: u64,
+    user: &Address,
+    total_deposit: i128,
+) -> Result<i128, VaultError> {
+    let mut campaign = storage::get_voucher_campaign(env, campaign_id)
+        .ok_or(VaultError::VoucherNotFound)?;
 
-    Ok(())
-}
\ No newline at end of file
+    if !campaign.active || env.ledger().timestamp() > campaign.expires_at {
+        return Err(VaultError::VoucherExpired);
+    }
+
+    let redemptions = storage::get_voucher_redemptions(env, campaign_id, user);
+    if redemptions >= campaign.per_user_limit {
+        return Err(VaultError::VoucherLimitReached);
+    }
+
+    let subsidy = match campaign.discount {
+        VoucherDiscount::Bps(bps) => {
+            total_deposit
+                .checked_mul(bps as i128)
+                .ok_or(VaultError::InvalidAmount)?
+                / BPS_DENOMINATOR
+        }
+        VoucherDiscount::Fixed(amount) => amount.min(total_deposit),
+    };
+
+    if subsidy > campaign.budget {
+        return Err(VaultError::VoucherBudgetExhausted);
+    }
+
+    campaign.budget -= subsidy;
+    storage::save_voucher_campaign(env, &campaign);
+    storage::set_voucher_redemptions(env, campaign_id, user, redemptions + 1);
+
+    Ok(subsidy)
+}
+
+/// Whether the booking's deposit is large enough to need M-of-N oracle reports
+fn requires_quorum(env: &Env, booking: &BookingRecord) -> bool {
+    match storage::get_oracle_quorum(env) {
+        Some(quorum) => booking.total_deposit >= quorum.min_deposit,
+        None => false,
+    }
+}
+
+pub fn set_oracle_quorum(
+    env: &Env,
+    oracles: Vec<Address>,
+    threshold: u32,
+    min_deposit: i128,
+) -> Result<(), VaultError> {
+    // 1. Require admin authorization
+    let admin = storage::get_admin(env).ok_or(VaultError::NotInitialized)?;
+    admin.require_auth();
+
+    // 2. Validate the oracle set and threshold
+    if threshold == 0 || threshold > oracles.len() || min_deposit <= 0 {
+        return Err(VaultError::InvalidQuorum);
+    }
+
+    for (i, oracle) in oracles.iter().enumerate() {
+        if oracles.first_index_of(&oracle) != Some(i as u32) {
+            return Err(VaultError::InvalidQuorum);
+        }
+    }
+
+    // 3. Save configuration
+    storage::set_oracle_quorum(
+        env,
+        &OracleQuorum {
+            oracles,
+            threshold,
+            min_deposit,
+        },
+    );
+
+    Ok(())
+}
+
+pub fn submit_duration_report(
+    env: &Env,
+    oracle: &Address,
+    booking_id: u64,
+    actual_duration: u64,
+) -> Result<(), VaultError> {
+    // 1. Require oracle authorization
+    oracle.require_auth();
+
+    // 2. Verify the caller belongs to the oracle set
+    let quorum = storage::get_oracle_quorum(env).ok_or(VaultError::QuorumNotRequired)?;
+    if !quorum.oracles.contains(oracle) {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    // 3. Get booking and verify it is a pending high-value booking
+    let booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    if booking.status != BookingStatus::Pending {
+        return Err(VaultError::BookingNotPending);
+    }
+
+    if booking.stream {
+        return Err(VaultError::InvalidBookingMode);
+    }
+
+    if booking.total_deposit < quorum.min_deposit {
+        return Err(VaultError::QuorumNotRequired);
+    }
+
+    check_finalization_window(env, &booking)?;
+
+    // 4. Each oracle may report once per booking
+    let mut reports = storage::get_duration_reports(env, booking_id);
+    if reports.iter().any(|report| report.oracle == *oracle) {
+        return Err(VaultError::DuplicateReport);
+    }
+
+    let conflicting = reports
+        .iter()
+        .any(|report| report.actual_duration != actual_duration);
+
+    reports.push_back(DurationReport {
+        oracle: oracle.clone(),
+        actual_duration,
+    });
+    storage::save_duration_reports(env, booking_id, &reports);
+    events::duration_reported(env, &booking, oracle, actual_duration);
+
+    // 5. Disagreement freezes the booking until the admin resolves it
+    if conflicting {
+        storage::update_booking_status(env, booking_id, BookingStatus::Disputed);
+        events::session_disputed(env, &booking);
+        return Ok(());
+    }
+
+    // 6. Settle once enough matching reports are in
+    if reports.len() >= quorum.threshold {
+        settle_booking(env, &booking, actual_duration)?;
+    }
+
+    Ok(())
+}
+
+pub fn resolve_dispute(
+    env: &Env,
+    booking_id: u64,
+    actual_duration: u64,
+) -> Result<(), VaultError> {
+    // 1. Require admin authorization
+    let admin = storage::get_admin(env).ok_or(VaultError::NotInitialized)?;
+    admin.require_auth();
+
+    // 2. Get booking and verify it is disputed
+    let booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    if booking.status != BookingStatus::Disputed {
+        return Err(VaultError::BookingNotDisputed);
+    }
+
+    // 3. Settle with the admin-determined duration
+    settle_booking(env, &booking, actual_duration)
+}
+
+pub fn reclaim_stale_session(
+    env: &Env,
+    user: &Address,
+    booking_id: u64,
+) -> Result<(), VaultError> {
+    // 1. Require user authorization
+    user.require_auth();
+
+    // 2. Get booking and verify it exists
+    let booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    // 3. Verify the caller is the booking owner
+    if booking.user != *user {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    // 4. Verify booking is in Pending status
+    if booking.status != BookingStatus::Pending {
+        return Err(VaultError::BookingNotPending);
+    }
+
+    // Streams are never stale; the user stops them with stop_stream
+    if booking.stream {
+        return Err(VaultError::InvalidBookingMode);
+    }
+
+    // 5. Check that the booking's window has passed
+    let current_time = env.ledger().timestamp();
+    if current_time <= booking.expires_at {
+        return Err(VaultError::ReclaimTooEarly);
+    }
+
+    // 6. Transfer total_deposit back to user
+    refund_booking(env, &booking, booking.total_deposit)?;
+
+    // 7. Update booking status to Reclaimed
+    close_booking(env, &booking, BookingStatus::Reclaimed);
+    record_reclaim(env, &booking.expert);
+
+    // 8. Emit event
+    events::session_reclaimed(
+        env,
+        booking_id,
+        &booking.user,
+        &booking.expert,
+        booking.total_deposit,
+    );
+
+    Ok(())
+}
+
+pub fn expire_booking(
+    env: &Env,
+    keeper: &Address,
+    booking_id: u64,
+) -> Result<(), VaultError> {
+    // 1. Require keeper authorization; anyone may call this
+    keeper.require_auth();
+
+    // 2. Get booking and verify it exists
+    let booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    // 3. Verify booking is in Pending status
+    if booking.status != BookingStatus::Pending {
+        return Err(VaultError::BookingNotPending);
+    }
+
+    // Streams are never stale; the user stops them with stop_stream
+    if booking.stream {
+        return Err(VaultError::InvalidBookingMode);
+    }
+
+    // 4. Check that the booking's window has passed
+    if env.ledger().timestamp() <= booking.expires_at {
+        return Err(VaultError::BookingNotExpired);
+    }
+
+    // 5. Refund the user and pay the keeper
+    refund_booking(env, &booking, booking.total_deposit)?;
+
+    let bounty = pay_keeper_bounty(env, keeper);
+
+    // 6. Update booking status to Expired
+    close_booking(env, &booking, BookingStatus::Expired);
+    record_reclaim(env, &booking.expert);
+
+    // 7. Emit event
+    events::booking_expired(
+        env,
+        booking_id,
+        &booking.user,
+        &booking.expert,
+        keeper,
+        booking.total_deposit,
+        bounty,
+    );
+
+    Ok(())
+}
+
+/// Pays the keeper bounty, limited to what the fee pool can cover, and returns the amount paid
+fn pay_keeper_bounty(env: &Env, keeper: &Address) -> i128 {
+    let fee_pool = storage::get_fee_pool(env);
+    let bounty = storage::get_keeper_bounty(env).min(fee_pool);
+
+    if bounty > 0 {
+        storage::set_fee_pool(env, fee_pool - bounty);
+
+        let token_address = storage::get_token(env);
+        let token_client = token::Client::new(env, &token_address);
+        token_client.transfer(&env.current_contract_address(), keeper, &bounty);
+    }
+
+    bounty
+}
+
+pub fn set_platform_fee(env: &Env, fee_bps: u32) -> Result<(), VaultError> {
+    let admin = storage::get_admin(env).ok_or(VaultError::NotInitialized)?;
+    admin.require_auth();
+
+    if fee_bps as i128 > BPS_DENOMINATOR {
+        return Err(VaultError::InvalidFee);
+    }
+
+    storage::set_platform_fee_bps(env, fee_bps);
+
+    Ok(())
+}
+
+pub fn set_keeper_bounty(env: &Env, amount: i128) -> Result<(), VaultError> {
+    let admin = storage::get_admin(env).ok_or(VaultError::NotInitialized)?;
+    admin.require_auth();
+
+    if amount < 0 {
+        return Err(VaultError::InvalidAmount);
+    }
+
+    storage::set_keeper_bounty(env, amount);
+
+    Ok(())
+}
+
+pub fn set_booking_limits(
+    env: &Env,
+    max_pending_per_user: u32,
+    max_pending_per_pair: u32,
+    min_deposit: i128,
+) -> Result<(), VaultError> {
+    let admin = storage::get_admin(env).ok_or(VaultError::NotInitialized)?;
+    admin.require_auth();
+
+    if min_deposit < 0 {
+        return Err(VaultError::InvalidAmount);
+    }
+
+    storage::set_booking_limits(
+        env,
+        &BookingLimits {
+            max_pending_per_user,
+            max_pending_per_pair,
+            min_deposit,
+        },
+    );
+
+    Ok(())
+}
+
+pub fn withdraw_fees(env: &Env, to: &Address, amount: i128) -> Result<(), VaultError> {
+    // 1. Require admin authorization
+    let admin = storage::get_admin(env).ok_or(VaultError::NotInitialized)?;
+    admin.require_auth();
+
+    // 2. Validate amount against the pool
+    if amount <= 0 {
+        return Err(VaultError::InvalidAmount);
+    }
+
+    let fee_pool = storage::get_fee_pool(env);
+    if amount > fee_pool {
+        return Err(VaultError::InsufficientFees);
+    }
+
+    // 3. Transfer fees out
+    storage::set_fee_pool(env, fee_pool - amount);
+
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    token_client.transfer(&env.current_contract_address(), to, &amount);
+
+    // 4. Emit event
+    events::fees_withdrawn(env, to, amount);
+
+    Ok(())
+}
+
+pub fn sweep(env: &Env, token_address: &Address, to: &Address) -> Result<i128, VaultError> {
+    // 1. Require admin authorization
+    let admin = storage::get_admin(env).ok_or(VaultError::NotInitialized)?;
+    admin.require_auth();
+
+    // 2. Only the balance above recorded liabilities can leave
+    let surplus = get_solvency(env, token_address).surplus;
+    if surplus <= 0 {
+        return Err(VaultError::InsufficientBalance);
+    }
+
+    // 3. Transfer the surplus out
+    let token_client = token::Client::new(env, token_address);
+    token_client.transfer(&env.current_contract_address(), to, &surplus);
+
+    // 4. Emit audit event
+    events::tokens_swept(env, token_address, &admin, to, surplus);
+
+    Ok(surplus)
+}
+
+pub fn reject_session(
+    env: &Env,
+    expert: &Address,
+    booking_id: u64,
+    reason: RejectionReason,
+    message_hash: Option<BytesN<32>>,
+) -> Result<(), VaultError> {
+    // 1. Require expert authorization
+    expert.require_auth();
+
+    // 2. Get booking and verify it exists
+    let mut booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    // 3. Verify the caller is the expert in the booking
+    if booking.expert != *expert {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    // A stream that has been stopped or has paid out cannot be refunded in full
+    if booking.stream && (booking.stopped_at.is_some() || booking.withdrawn > 0) {
+        return Err(VaultError::InvalidBookingMode);
+    }
+
+    // 4. Verify booking is in Pending status
+    if booking.status != BookingStatus::Pending {
+        return Err(VaultError::BookingNotPending);
+    }
+
+    // 5. Transfer total_deposit back to user
+    refund_booking(env, &booking, booking.total_deposit)?;
+
+    // 6. Update booking status to Rejected along with the reason
+    booking.status = BookingStatus::Rejected;
+    booking.rejection_reason = OptionalRejectionReason::Some(reason);
+    booking.rejection_hash = message_hash;
+    storage::save_booking(env, &booking);
+    release_booking(env, &booking);
+
+    let mut stats = storage::get_expert_stats(env, expert);
+    stats.rejections += 1;
+    storage::save_expert_stats(env, expert, &stats);
+
+    // 7. Emit event
+    events::session_rejected(env, &booking);
+
+    Ok(())
+}
+
+pub fn counter_propose(
+    env: &Env,
+    expert: &Address,
+    booking_id: u64,
+    new_rate: i128,
+    new_duration: u64,
+    new_start: u64,
+) -> Result<(), VaultError> {
+    // 1. Require expert authorization
+    expert.require_auth();
+
+    // 2. Get booking and verify the caller is its expert
+    let booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    if booking.expert != *expert {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    if booking.status != BookingStatus::Pending {
+        return Err(VaultError::BookingNotPending);
+    }
+
+    // A booking whose window has closed can only be reclaimed or expired
+    if env.ledger().timestamp() > booking.expires_at {
+        return Err(VaultError::BookingExpired);
+    }
+
+    // Streams accrue against their original terms, and pools fund a fixed draw
+    if booking.stream || booking.funding.pool_id.is_some() {
+        return Err(VaultError::InvalidBookingMode);
+    }
+
+    // 3. Price the revised terms; a voucher subsidy stays with the booking
+    let pricing = flat_pricing(env, new_rate)?;
+    let total_deposit = pricing_cost(&pricing, new_duration)?;
+
+    if total_deposit <= 0 || total_deposit < booking.funding.subsidy {
+        return Err(VaultError::InvalidAmount);
+    }
+
+    if total_deposit < storage::get_booking_limits(env).min_deposit {
+        return Err(VaultError::DepositBelowMinimum);
+    }
+
+    booking_window(env, new_start, new_duration, grace_period(&booking))?;
+
+    // 4. Store the proposal, replacing any earlier one
+    let proposal = CounterProposal {
+        rate_per_second: new_rate,
+        max_duration: new_duration,
+        scheduled_start: new_start,
+        total_deposit,
+        proposed_at: env.ledger().timestamp(),
+    };
+    storage::save_counter_proposal(env, booking_id, &proposal);
+
+    events::counter_proposed(env, &booking, &proposal);
+
+    Ok(())
+}
+
+pub fn accept_counter_proposal(
+    env: &Env,
+    user: &Address,
+    booking_id: u64,
+) -> Result<(), VaultError> {
+    // 1. Require user authorization
+    user.require_auth();
+
+    // 2. Get booking and verify the caller is its user
+    let mut booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    if booking.user != *user {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    if booking.status != BookingStatus::Pending {
+        return Err(VaultError::BookingNotPending);
+    }
+
+    if env.ledger().timestamp() > booking.expires_at {
+        return Err(VaultError::BookingExpired);
+    }
+
+    let proposal = storage::get_counter_proposal(env, booking_id)
+        .ok_or(VaultError::ProposalNotFound)?;
+
+    // 3. The proposed start must still be ahead of the ledger
+    let expires_at = booking_window(
+        env,
+        proposal.scheduled_start,
+        proposal.max_duration,
+        grace_period(&booking),
+    )?;
+
+    // 4. Collect or refund the difference in deposit
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    let difference = proposal.total_deposit - booking.total_deposit;
+
+    if difference > 0 {
+        token_client.transfer(user, &contract_address, &difference);
+    } else if difference < 0 {
+        token_client.transfer(&contract_address, user, &-difference);
+    }
+    storage::adjust_locked(env, difference);
+
+    // 5. Apply the revised terms in place
+    booking.rate_per_second = proposal.rate_per_second;
+    booking.pricing = flat_pricing(env, proposal.rate_per_second)?;
+    booking.max_duration = proposal.max_duration;
+    booking.total_deposit = proposal.total_deposit;
+    booking.scheduled_start = proposal.scheduled_start;
+    booking.expires_at = expires_at;
+    storage::save_booking(env, &booking);
+    storage::remove_counter_proposal(env, booking_id);
+    storage::remove_reschedule_proposal(env, booking_id);
+
+    // 6. Reports against the old terms no longer apply; whether the new deposit
+    // needs a quorum is decided again when the booking is settled
+    storage::remove_duration_reports(env, booking_id);
+
+    events::counter_accepted(env, &booking, difference);
+
+    Ok(())
+}
+
+pub fn propose_reschedule(
+    env: &Env,
+    caller: &Address,
+    booking_id: u64,
+    new_start: u64,
+) -> Result<(), VaultError> {
+    // 1. Require caller authorization
+    caller.require_auth();
+
+    // 2. Get booking and verify the caller is one of its parties
+    let booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    if booking.user != *caller && booking.expert != *caller {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    if booking.status != BookingStatus::Pending {
+        return Err(VaultError::BookingNotPending);
+    }
+
+    // A booking whose window has closed can only be reclaimed or expired
+    if env.ledger().timestamp() > booking.expires_at {
+        return Err(VaultError::BookingExpired);
+    }
+
+    // Streams accrue from their start, so it cannot move
+    if booking.stream {
+        return Err(VaultError::InvalidBookingMode);
+    }
+
+    // 3. The new start must be ahead of the ledger
+    booking_window(env, new_start, booking.max_duration, grace_period(&booking))?;
+
+    // 4. Store the proposal, replacing any earlier one from either party
+    let proposal = RescheduleProposal {
+        proposer: caller.clone(),
+        new_start,
+        proposed_at: env.ledger().timestamp(),
+    };
+    storage::save_reschedule_proposal(env, booking_id, &proposal);
+
+    events::reschedule_proposed(env, &booking, caller, new_start);
+
+    Ok(())
+}
+
+pub fn accept_reschedule(env: &Env, caller: &Address, booking_id: u64) -> Result<(), VaultError> {
+    // 1. Require caller authorization
+    caller.require_auth();
+
+    // 2. Get booking and its proposal
+    let mut booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    if booking.status != BookingStatus::Pending {
+        return Err(VaultError::BookingNotPending);
+    }
+
+    if env.ledger().timestamp() > booking.expires_at {
+        return Err(VaultError::BookingExpired);
+    }
+
+    let proposal = storage::get_reschedule_proposal(env, booking_id)
+        .ok_or(VaultError::ProposalNotFound)?;
+
+    // 3. Only the party who did not propose may accept
+    let counterparty = if proposal.proposer == booking.user {
+        &booking.expert
+    } else {
+        &booking.user
+    };
+    if counterparty != caller {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    // 4. The proposed start must still be ahead of the ledger
+    let expires_at = booking_window(
+        env,
+        proposal.new_start,
+        booking.max_duration,
+        grace_period(&booking),
+    )?;
+
+    // 5. Move the booking in place and record the move
+    let previous_start = booking.scheduled_start;
+    booking.scheduled_start = proposal.new_start;
+    booking.expires_at = expires_at;
+    storage::save_booking(env, &booking);
+    storage::remove_reschedule_proposal(env, booking_id);
+
+    // Reports on the session in its old window do not carry over to the new one
+    storage::remove_duration_reports(env, booking_id);
+
+    storage::add_reschedule_record(
+        env,
+        booking_id,
+        RescheduleRecord {
+            proposer: proposal.proposer,
+            previous_start,
+            new_start: proposal.new_start,
+            accepted_at: env.ledger().timestamp(),
+        },
+    );
+
+    events::reschedule_accepted(env, &booking, previous_start);
+
+    Ok(())
+}
+
+/// Grace period the booking was created with, recovered from its finalization window
+fn grace_period(booking: &BookingRecord) -> u64 {
+    booking.expires_at - booking.scheduled_start - booking.max_duration
+}
+
+pub fn tip(env: &Env, user: &Address, booking_id: u64, amount: i128) -> Result<(), VaultError> {
+    // 1. Require user authorization
+    user.require_auth();
+
+    if amount <= 0 {
+        return Err(VaultError::InvalidAmount);
+    }
+
+    // 2. Get booking and verify the tipper is its user
+    let mut booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    if booking.user != *user {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    // 3. Only completed sessions can be tipped
+    if booking.status != BookingStatus::Complete {
+        return Err(VaultError::BookingNotComplete);
+    }
+
+    // 4. Pass the tip through the vault to the expert, so the user only ever pays the vault
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(user, &contract_address, &amount);
+    token_client.transfer(&contract_address, &booking.expert, &amount);
+
+    // 5. Record the running total on the booking
+    booking.total_tips = booking
+        .total_tips
+        .checked_add(amount)
+        .ok_or(VaultError::InvalidAmount)?;
+    storage::save_booking(env, &booking);
+
+    events::session_tipped(env, &booking, amount);
+
+    Ok(())
+}
+pub fn create_engagement(
+    env: &Env,
+    user: &Address,
+    expert: &Address,
+    milestones: Vec<i128>,
+    approval_timeout: u64,
+) -> Result<u64, MarketError> {
+    // 1. Require authorization from the user funding the engagement
+    user.require_auth();
+
+    // 2. Validate milestones and approval timeout
+    if milestones.is_empty() || milestones.len() > MAX_MILESTONES {
+        return Err(MarketError::InvalidMilestones);
+    }
+
+    if approval_timeout == 0 || approval_timeout > MAX_APPROVAL_TIMEOUT {
+        return Err(MarketError::InvalidMilestones);
+    }
+
+    let mut total_deposit: i128 = 0;
+    for amount in milestones.iter() {
+        if amount <= 0 {
+            return Err(MarketError::InvalidMilestones);
+        }
+        total_deposit = total_deposit
+            .checked_add(amount)
+            .ok_or(MarketError::InvalidAmount)?;
+    }
+
+    // 3. The expert must be taking bookings from this user; enforce the anti-spam limits
+    check_expert_available(env, user, expert).map_err(market_error)?;
+    check_booking_limits(env, user, expert, total_deposit).map_err(market_error)?;
+
+    // 4. Transfer the full engagement amount into escrow
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(user, &contract_address, &total_deposit);
+    storage::adjust_locked(env, total_deposit);
+
+    // 5. Create engagement under the shared booking ID sequence
+    let engagement_id = storage::get_next_booking_id(env);
+    let engagement = EngagementRecord {
+        id: engagement_id,
+        user: user.clone(),
+        expert: expert.clone(),
+        milestones,
+        next_milestone: 0,
+        submitted_at: None,
+        approval_timeout,
+        total_deposit,
+        released: 0,
+        status: BookingStatus::Pending,
+        created_at: env.ledger().timestamp(),
+    };
+    storage::save_engagement(env, &engagement);
+
+    // 6. Index alongside the parties' bookings
+    storage::add_booking_to_user_list(env, user, engagement_id);
+    storage::add_booking_to_expert_list(env, expert, engagement_id);
+    storage::increment_open_bookings(env, user, expert);
+
+    events::booking_created(env, engagement_id, user, expert, total_deposit);
+
+    Ok(engagement_id)
+}
+
+pub fn submit_milestone(
+    env: &Env,
+    expert: &Address,
+    engagement_id: u64,
+) -> Result<(), MarketError> {
+    // 1. Require expert authorization
+    expert.require_auth();
+
+    // 2. Get engagement and verify the caller and status
+    let mut engagement = storage::get_engagement(env, engagement_id)
+        .ok_or(MarketError::EngagementNotFound)?;
+
+    if engagement.expert != *expert {
+        return Err(MarketError::NotAuthorized);
+    }
+
+    if engagement.status != BookingStatus::Pending {
+        return Err(MarketError::BookingNotPending);
+    }
+
+    // 3. Only one milestone can await approval at a time
+    if engagement.submitted_at.is_some() {
+        return Err(MarketError::MilestoneAlreadySubmitted);
+    }
+
+    engagement.submitted_at = Some(env.ledger().timestamp());
+    storage::save_engagement(env, &engagement);
+
+    events::milestone_submitted(env, &engagement);
+
+    Ok(())
+}
+
+pub fn approve_milestone(
+    env: &Env,
+    user: &Address,
+    engagement_id: u64,
+) -> Result<(), MarketError> {
+    // 1. Require user authorization
+    user.require_auth();
+
+    // 2. Get engagement and verify the caller
+    let engagement = storage::get_engagement(env, engagement_id)
+        .ok_or(MarketError::EngagementNotFound)?;
+
+    if engagement.user != *user {
+        return Err(MarketError::NotAuthorized);
+    }
+
+    release_milestone(env, engagement, false)
+}
+
+pub fn auto_approve_milestone(env: &Env, engagement_id: u64) -> Result<(), MarketError> {
+    // 1. Get engagement; anyone may trigger auto-approval
+    let engagement = storage::get_engagement(env, engagement_id)
+        .ok_or(MarketError::EngagementNotFound)?;
+
+    // 2. Verify the approval timeout has elapsed since submission
+    let submitted_at = engagement
+        .submitted_at
+        .ok_or(MarketError::MilestoneNotSubmitted)?;
+
+    if env.ledger().timestamp() < submitted_at + engagement.approval_timeout {
+        return Err(MarketError::ApprovalTimeoutNotReached);
+    }
+
+    release_milestone(env, engagement, true)
+}
+
+/// Pays out the submitted milestone and advances to the next one
+fn release_milestone(
+    env: &Env,
+    mut engagement: EngagementRecord,
+    auto: bool,
+) -> Result<(), MarketError> {
+    // 1. Verify status and that a milestone is awaiting approval
+    if engagement.status != BookingStatus::Pending {
+        return Err(MarketError::BookingNotPending);
+    }
+
+    if engagement.submitted_at.is_none() {
+        return Err(MarketError::MilestoneNotSubmitted);
+    }
+
+    // 2. Pay the expert, net of the platform fee
+    let milestone = engagement.next_milestone;
+    let amount = engagement.milestones.get_unchecked(milestone);
+    let fee = platform_fee(env, amount).map_err(|_| MarketError::InvalidAmount)?;
+
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(&contract_address, &engagement.expert, &(amount - fee));
+
+    if fee > 0 {
+        storage::set_fee_pool(env, storage::get_fee_pool(env) + fee);
+    }
+    storage::adjust_locked(env, -amount);
+
+    // 3. Advance the engagement
+    engagement.released += amount;
+    engagement.next_milestone += 1;
+    engagement.submitted_at = None;
+    if engagement.next_milestone == engagement.milestones.len() {
+        engagement.status = BookingStatus::Complete;
+        storage::decrement_open_bookings(env, &engagement.user, &engagement.expert);
+    }
+    storage::save_engagement(env, &engagement);
+
+    events::milestone_released(env, &engagement, milestone, amount, auto);
+
+    Ok(())
+}
+
+pub fn cancel_engagement(
+    env: &Env,
+    user: &Address,
+    engagement_id: u64,
+) -> Result<(), MarketError> {
+    // 1. Require user authorization
+    user.require_auth();
+
+    // 2. Get engagement and verify the caller and status
+    let mut engagement = storage::get_engagement(env, engagement_id)
+        .ok_or(MarketError::EngagementNotFound)?;
+
+    if engagement.user != *user {
+        return Err(MarketError::NotAuthorized);
+    }
+
+    if engagement.status != BookingStatus::Pending {
+        return Err(MarketError::BookingNotPending);
+    }
+
+    // 3. A submitted milestone must be approved or auto-approved first
+    if engagement.submitted_at.is_some() {
+        return Err(MarketError::MilestoneAlreadySubmitted);
+    }
+
+    // 4. Refund milestones that were never released
+    let refund = engagement.total_deposit - engagement.released;
+
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(&contract_address, &engagement.user, &refund);
+    storage::adjust_locked(env, -refund);
+
+    engagement.status = BookingStatus::Reclaimed;
+    storage::save_engagement(env, &engagement);
+    storage::decrement_open_bookings(env, &engagement.user, &engagement.expert);
+
+    events::session_reclaimed(
+        env,
+        engagement_id,
+        &engagement.user,
+        &engagement.expert,
+        refund,
+    );
+
+    Ok(())
+}
+
+pub fn ask_question(
+    env: &Env,
+    user: &Address,
+    expert: &Address,
+    fee: i128,
+    question_hash: BytesN<32>,
+    deadline: u64,
+) -> Result<u64, MarketError> {
+    // 1. Require authorization from the user asking
+    user.require_auth();
+
+    // 2. Validate fee and deadline
+    if fee <= 0 {
+        return Err(MarketError::InvalidAmount);
+    }
+
+    let now = env.ledger().timestamp();
+    if deadline <= now {
+        return Err(MarketError::InvalidSchedule);
+    }
+
+    // 3. The expert must be taking questions from this user; enforce the anti-spam limits
+    check_expert_available(env, user, expert).map_err(market_error)?;
+    check_booking_limits(env, user, expert, fee).map_err(market_error)?;
+
+    // 4. Transfer the fee into escrow
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(user, &contract_address, &fee);
+    storage::adjust_locked(env, fee);
+
+    // 5. Create question under the shared booking ID sequence
+    let question_id = storage::get_next_booking_id(env);
+    let question = QuestionRecord {
+        id: question_id,
+        user: user.clone(),
+        expert: expert.clone(),
+        fee,
+        question_hash,
+        answer_hash: None,
+        deadline,
+        status: BookingStatus::Pending,
+        created_at: now,
+    };
+    storage::save_question(env, &question);
+
+    // 6. Index alongside the parties' bookings
+    storage::add_booking_to_user_list(env, user, question_id);
+    storage::add_booking_to_expert_list(env, expert, question_id);
+    storage::increment_open_bookings(env, user, expert);
+
+    events::booking_created(env, question_id, user, expert, fee);
+
+    Ok(question_id)
+}
+
+pub fn answer_question(
+    env: &Env,
+    expert: &Address,
+    question_id: u64,
+    answer_hash: BytesN<32>,
+) -> Result<(), MarketError> {
+    // 1. Require expert authorization
+    expert.require_auth();
+
+    // 2. Get question and verify the caller and status
+    let mut question = storage::get_question(env, question_id)
+        .ok_or(MarketError::QuestionNotFound)?;
+
+    if question.expert != *expert {
+        return Err(MarketError::NotAuthorized);
+    }
+
+    if question.status != BookingStatus::Pending {
+        return Err(MarketError::BookingNotPending);
+    }
+
+    // 3. Answers after the deadline are not paid
+    if env.ledger().timestamp() > question.deadline {
+        return Err(MarketError::BookingExpired);
+    }
+
+    // 4. Pay the expert, net of the platform fee
+    let fee = platform_fee(env, question.fee).map_err(|_| MarketError::InvalidAmount)?;
+
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(&contract_address, &question.expert, &(question.fee - fee));
+
+    if fee > 0 {
+        storage::set_fee_pool(env, storage::get_fee_pool(env) + fee);
+    }
+    storage::adjust_locked(env, -question.fee);
+
+    // 5. Record the answer and complete the question
+    question.answer_hash = Some(answer_hash.clone());
+    question.status = BookingStatus::Complete;
+    storage::save_question(env, &question);
+    storage::decrement_open_bookings(env, &question.user, &question.expert);
+
+    events::question_answered(env, &question, &answer_hash);
+
+    Ok(())
+}
+
+pub fn expire_question(
+    env: &Env,
+    keeper: &Address,
+    question_id: u64,
+) -> Result<(), MarketError> {
+    // 1. Require keeper authorization; anyone may call this
+    keeper.require_auth();
+
+    // 2. Get question and verify it is unanswered past its deadline
+    let mut question = storage::get_question(env, question_id)
+        .ok_or(MarketError::QuestionNotFound)?;
+
+    if question.status != BookingStatus::Pending {
+        return Err(MarketError::BookingNotPending);
+    }
+
+    if env.ledger().timestamp() <= question.deadline {
+        return Err(MarketError::BookingNotExpired);
+    }
+
+    // 3. Refund the user and pay the keeper
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(&contract_address, &question.user, &question.fee);
+    storage::adjust_locked(env, -question.fee);
+
+    let bounty = pay_keeper_bounty(env, keeper);
+
+    // 4. Update question status to Expired
+    question.status = BookingStatus::Expired;
+    storage::save_question(env, &question);
+    storage::decrement_open_bookings(env, &question.user, &question.expert);
+
+    events::booking_expired(
+        env,
+        question_id,
+        &question.user,
+        &question.expert,
+        keeper,
+        question.fee,
+        bounty,
+    );
+
+    Ok(())
+}
+
+pub fn set_registry(env: &Env, registry: &Address) -> Result<(), VaultError> {
+    let admin = storage::get_admin(env).ok_or(VaultError::NotInitialized)?;
+    admin.require_auth();
+
+    storage::set_registry(env, registry);
+
+    Ok(())
+}
+
+pub fn post_bounty(
+    env: &Env,
+    poster: &Address,
+    amount: i128,
+    question_hash: BytesN<32>,
+    expires_at: u64,
+) -> Result<u64, MarketError> {
+    // 1. Require authorization from the poster
+    poster.require_auth();
+
+    // 2. Validate amount and expiry
+    if amount <= 0 {
+        return Err(MarketError::InvalidAmount);
+    }
+
+    let now = env.ledger().timestamp();
+    if expires_at <= now {
+        return Err(MarketError::InvalidSchedule);
+    }
+
+    // 3. Transfer the bounty into escrow
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(poster, &contract_address, &amount);
+    storage::adjust_locked(env, amount);
+
+    // 4. Create bounty
+    let bounty_id = storage::get_next_bounty_id(env);
+    let bounty = BountyRecord {
+        id: bounty_id,
+        poster: poster.clone(),
+        amount,
+        question_hash,
+        expires_at,
+        submission_count: 0,
+        status: BookingStatus::Pending,
+        created_at: now,
+    };
+    storage::save_bounty(env, &bounty);
+
+    events::bounty_posted(env, &bounty);
+
+    Ok(bounty_id)
+}
+
+pub fn submit_bounty_answer(
+    env: &Env,
+    expert: &Address,
+    bounty_id: u64,
+    content_hash: BytesN<32>,
+) -> Result<u32, MarketError> {
+    // 1. Require expert authorization
+    expert.require_auth();
+
+    // 2. Verify the expert against the identity registry
+    let registry = storage::get_registry(env).ok_or(MarketError::RegistryNotSet)?;
+    if !IdentityRegistryClient::new(env, &registry).is_verified(expert) {
+        return Err(MarketError::ExpertNotVerified);
+    }
+
+    // 3. Get bounty and verify it is still open
+    let mut bounty = storage::get_bounty(env, bounty_id)
+        .ok_or(MarketError::BountyNotFound)?;
+
+    if bounty.status != BookingStatus::Pending {
+        return Err(MarketError::BookingNotPending);
+    }
+
+    if env.ledger().timestamp() > bounty.expires_at {
+        return Err(MarketError::BookingExpired);
+    }
+
+    // 4. One answer per expert, up to the submission cap
+    if storage::has_bounty_submission(env, bounty_id, expert) {
+        return Err(MarketError::AlreadySubmitted);
+    }
+
+    if bounty.submission_count >= MAX_BOUNTY_SUBMISSIONS {
+        return Err(MarketError::TooManySubmissions);
+    }
+
+    let index = bounty.submission_count;
+    let submission = BountySubmission {
+        expert: expert.clone(),
+        content_hash,
+        submitted_at: env.ledger().timestamp(),
+    };
+    storage::save_bounty_submission(env, bounty_id, index, &submission);
+
+    bounty.submission_count += 1;
+    storage::save_bounty(env, &bounty);
+
+    events::bounty_answered(env, &bounty, expert, index);
+
+    Ok(index)
+}
+
+pub fn award_bounty(
+    env: &Env,
+    poster: &Address,
+    bounty_id: u64,
+    awards: Vec<BountyAward>,
+) -> Result<(), MarketError> {
+    // 1. Require poster authorization
+    poster.require_auth();
+
+    // 2. Get bounty and verify the caller and status
+    let mut bounty = storage::get_bounty(env, bounty_id)
+        .ok_or(MarketError::BountyNotFound)?;
+
+    if bounty.poster != *poster {
+        return Err(MarketError::NotAuthorized);
+    }
+
+    if bounty.status != BookingStatus::Pending {
+        return Err(MarketError::BookingNotPending);
+    }
+
+    // Past the award deadline the bounty is split by settle_bounty instead
+    if env.ledger().timestamp() > bounty.expires_at + BOUNTY_AWARD_WINDOW {
+        return Err(MarketError::BookingExpired);
+    }
+
+    // 3. Awards must name distinct submissions and split the full bounty
+    if awards.is_empty() {
+        return Err(MarketError::InvalidAwards);
+    }
+
+    let mut total: i128 = 0;
+    for (i, award) in awards.iter().enumerate() {
+        if award.submission >= bounty.submission_count || award.amount <= 0 {
+            return Err(MarketError::InvalidAwards);
+        }
+        if awards.iter().skip(i + 1).any(|other| other.submission == award.submission) {
+            return Err(MarketError::InvalidAwards);
+        }
+        total = total
+            .checked_add(award.amount)
+            .ok_or(MarketError::InvalidAwards)?;
+    }
+
+    if total != bounty.amount {
+        return Err(MarketError::InvalidAwards);
+    }
+
+    // 4. Pay each winner, net of the platform fee
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+
+    let mut fees: i128 = 0;
+    for award in awards.iter() {
+        let submission = storage::get_bounty_submission(env, bounty_id, award.submission)
+            .ok_or(MarketError::InvalidAwards)?;
+        let fee = platform_fee(env, award.amount).map_err(|_| MarketError::InvalidAmount)?;
+
+        token_client.transfer(&contract_address, &submission.expert, &(award.amount - fee));
+        fees += fee;
+
+        events::bounty_awarded(env, &bounty, &submission.expert, award.amount);
+    }
+
+    if fees > 0 {
+        storage::set_fee_pool(env, storage::get_fee_pool(env) + fees);
+    }
+    storage::adjust_locked(env, -bounty.amount);
+
+    // 5. Update bounty status to Complete
+    bounty.status = BookingStatus::Complete;
+    storage::save_bounty(env, &bounty);
+
+    Ok(())
+}
+
+pub fn reclaim_bounty(
+    env: &Env,
+    poster: &Address,
+    bounty_id: u64,
+) -> Result<(), MarketError> {
+    // 1. Require poster authorization
+    poster.require_auth();
+
+    // 2. Get bounty and verify the caller and status
+    let mut bounty = storage::get_bounty(env, bounty_id)
+        .ok_or(MarketError::BountyNotFound)?;
+
+    if bounty.poster != *poster {
+        return Err(MarketError::NotAuthorized);
+    }
+
+    if bounty.status != BookingStatus::Pending {
+        return Err(MarketError::BookingNotPending);
+    }
+
+    // 3. Only unanswered bounties can be reclaimed, and only after expiry
+    if env.ledger().timestamp() <= bounty.expires_at {
+        return Err(MarketError::ReclaimTooEarly);
+    }
+
+    if bounty.submission_count > 0 {
+        return Err(MarketError::BountyHasSubmissions);
+    }
+
+    // 4. Refund the poster
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(&contract_address, &bounty.poster, &bounty.amount);
+    storage::adjust_locked(env, -bounty.amount);
+
+    bounty.status = BookingStatus::Reclaimed;
+    storage::save_bounty(env, &bounty);
+
+    events::bounty_reclaimed(env, &bounty);
+
+    Ok(())
+}
+
+pub fn settle_bounty(env: &Env, bounty_id: u64) -> Result<(), MarketError> {
+    // 1. Get bounty; anyone may settle once the poster's award window has passed
+    let mut bounty = storage::get_bounty(env, bounty_id)
+        .ok_or(MarketError::BountyNotFound)?;
+
+    if bounty.status != BookingStatus::Pending {
+        return Err(MarketError::BookingNotPending);
+    }
+
+    if env.ledger().timestamp() <= bounty.expires_at + BOUNTY_AWARD_WINDOW {
+        return Err(MarketError::BookingNotExpired);
+    }
+
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+
+    // 2. No answers: refund the poster
+    if bounty.submission_count == 0 {
+        token_client.transfer(&contract_address, &bounty.poster, &bounty.amount);
+        storage::adjust_locked(env, -bounty.amount);
+
+        bounty.status = BookingStatus::Reclaimed;
+        storage::save_bounty(env, &bounty);
+
+        events::bounty_reclaimed(env, &bounty);
+
+        return Ok(());
+    }
+
+    // 3. Split the bounty equally between all submissions, net of the platform fee.
+    // The rounding remainder goes to the first answer.
+    let count = bounty.submission_count as i128;
+    let share = bounty.amount / count;
+    let remainder = bounty.amount - share * count;
+
+    let mut fees: i128 = 0;
+    for index in 0..bounty.submission_count {
+        let submission = storage::get_bounty_submission(env, bounty_id, index)
+            .ok_or(MarketError::BountyNotFound)?;
+        let amount = if index == 0 { share + remainder } else { share };
+        let fee = platform_fee(env, amount).map_err(|_| MarketError::InvalidAmount)?;
+
+        token_client.transfer(&contract_address, &submission.expert, &(amount - fee));
+        fees += fee;
+
+        events::bounty_awarded(env, &bounty, &submission.expert, amount);
+    }
+
+    if fees > 0 {
+        storage::set_fee_pool(env, storage::get_fee_pool(env) + fees);
+    }
+    storage::adjust_locked(env, -bounty.amount);
+
+    // 4. Update bounty status to Complete
+    bounty.status = BookingStatus::Complete;
+    storage::save_bounty(env, &bounty);
+
+    Ok(())
+}
+
+/// Returns up to `limit` submissions starting at index `start`
+pub fn get_bounty_submissions(
+    env: &Env,
+    bounty_id: u64,
+    start: u32,
+    limit: u32,
+) -> Vec<BountySubmission> {
+    let mut submissions = Vec::new(env);
+
+    let count = match storage::get_bounty(env, bounty_id) {
+        Some(bounty) => bounty.submission_count,
+        None => return submissions,
+    };
+
+    let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+    for index in start..end {
+        if let Some(submission) = storage::get_bounty_submission(env, bounty_id, index) {
+            submissions.push_back(submission);
+        }
+    }
+
+    submissions
+}
+
+pub fn create_auction(
+    env: &Env,
+    expert: &Address,
+    reserve_price: i128,
+    max_duration: u64,
+    scheduled_start: u64,
+    grace_period: u64,
+    end_time: u64,
+) -> Result<u64, MarketError> {
+    // 1. Require expert authorization
+    expert.require_auth();
+
+    // 2. Validate the slot; the winning bid must cover at least 1 token per second
+    if max_duration == 0 || reserve_price < max_duration as i128 {
+        return Err(MarketError::InvalidAmount);
+    }
+
+    booking_window(env, scheduled_start, max_duration, grace_period)
+        .map_err(|_| MarketError::InvalidSchedule)?;
+
+    let now = env.ledger().timestamp();
+    if end_time <= now || end_time > scheduled_start {
+        return Err(MarketError::InvalidSchedule);
+    }
+
+    // 3. Create auction
+    let auction_id = storage::get_next_auction_id(env);
+    let auction = AuctionRecord {
+        id: auction_id,
+        expert: expert.clone(),
+        reserve_price,
+        max_duration,
+        scheduled_start,
+        grace_period,
+        end_time,
+        highest_bidder: None,
+        highest_bid: 0,
+        booking_id: None,
+        status: BookingStatus::Pending,
+        created_at: now,
+    };
+    storage::save_auction(env, &auction);
+
+    events::auction_created(env, &auction);
+
+    Ok(auction_id)
+}
+
+pub fn place_bid(
+    env: &Env,
+    bidder: &Address,
+    auction_id: u64,
+    amount: i128,
+) -> Result<(), MarketError> {
+    // 1. Require bidder authorization
+    bidder.require_auth();
+
+    // 2. Get auction and verify it is still open
+    let mut auction = storage::get_auction(env, auction_id)
+        .ok_or(MarketError::AuctionNotFound)?;
+
+    if auction.status != BookingStatus::Pending || env.ledger().timestamp() >= auction.end_time {
+        return Err(MarketError::AuctionEnded);
+    }
+
+    // 3. A bidder the expert has blocked, or any bidder once bookings are paused, cannot win
+    check_expert_available(env, bidder, &auction.expert).map_err(market_error)?;
+
+    // 4. Bid must meet the reserve and beat the current highest bid
+    if amount < auction.reserve_price || amount <= auction.highest_bid {
+        return Err(MarketError::BidTooLow);
+    }
+
+    // 5. Escrow the new bid and refund the outbid bidder
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(bidder, &contract_address, &amount);
+
+    if let Some(previous) = auction.highest_bidder.clone() {
+        token_client.transfer(&contract_address, &previous, &auction.highest_bid);
+    }
+    storage::adjust_locked(env, amount - auction.highest_bid);
+
+    auction.highest_bidder = Some(bidder.clone());
+    auction.highest_bid = amount;
+    storage::save_auction(env, &auction);
+
+    events::bid_placed(env, &auction, bidder, amount);
+
+    Ok(())
+}
+
+pub fn settle_auction(env: &Env, auction_id: u64) -> Result<Option<u64>, MarketError> {
+    // 1. Get auction; anyone may settle once bidding has closed
+    let mut auction = storage::get_auction(env, auction_id)
+        .ok_or(MarketError::AuctionNotFound)?;
+
+    if auction.status != BookingStatus::Pending {
+        return Err(MarketError::BookingNotPending);
+    }
+
+    if env.ledger().timestamp() < auction.end_time {
+        return Err(MarketError::AuctionNotEnded);
+    }
+
+    // 2. No bids: the slot simply expires
+    let winner = match auction.highest_bidder.clone() {
+        Some(winner) => winner,
+        None => {
+            auction.status = BookingStatus::Expired;
+            storage::save_auction(env, &auction);
+            events::auction_settled(env, &auction);
+            return Ok(None);
+        }
+    };
+
+    // 3. Turn the escrowed winning bid into a funded booking.
+    // The rate is the bid spread over the slot; any rounding remainder is refunded at settlement.
+    let pricing = Vec::from_array(
+        env,
+        [PricingTier {
+            threshold: 0,
+            rate_per_second: auction.highest_bid / auction.max_duration as i128,
+        }],
+    );
+    let expires_at = auction.scheduled_start + auction.max_duration + auction.grace_period;
+
+    let booking_id = record_booking(
+        env,
+        &winner,
+        &auction.expert,
+        pricing,
+        auction.max_duration,
+        auction.highest_bid,
+        auction.scheduled_start,
+        expires_at,
+        BookingFunding {
+            voucher_id: None,
+            subsidy: 0,
+            pool_id: None,
+        },
+        false,
+    );
+
+    auction.booking_id = Some(booking_id);
+    auction.status = BookingStatus::Complete;
+    storage::save_auction(env, &auction);
+
+    events::auction_settled(env, &auction);
+
+    Ok(Some(booking_id))
+}
+
+pub fn create_voucher_campaign(
+    env: &Env,
+    discount: VoucherDiscount,
+    budget: i128,
+    per_user_limit: u32,
+    expires_at: u64,
+    from_fee_pool: bool,
+) -> Result<u64, VaultError> {
+    // 1. Require admin authorization
+    let admin = storage::get_admin(env).ok_or(VaultError::NotInitialized)?;
+    admin.require_auth();
+
+    // 2. Validate campaign parameters
+    let valid_discount = match discount {
+        VoucherDiscount::Bps(bps) => bps > 0 && bps as i128 <= BPS_DENOMINATOR,
+        VoucherDiscount::Fixed(amount) => amount > 0,
+    };
+    if !valid_discount || budget <= 0 || per_user_limit == 0 {
+        return Err(VaultError::InvalidAmount);
+    }
+
+    let now = env.ledger().timestamp();
+    if expires_at <= now {
+        return Err(VaultError::InvalidSchedule);
+    }
+
+    // 3. Fund the budget from the fee pool or the admin's own tokens
+    if from_fee_pool {
+        let fee_pool = storage::get_fee_pool(env);
+        if budget > fee_pool {
+            return Err(VaultError::InsufficientFees);
+        }
+        storage::set_fee_pool(env, fee_pool - budget);
+    } else {
+        let token_address = storage::get_token(env);
+        let token_client = token::Client::new(env, &token_address);
+        let contract_address = env.current_contract_address();
+        token_client.transfer(&admin, &contract_address, &budget);
+    }
+    storage::adjust_locked(env, budget);
+
+    // 4. Create campaign
+    let campaign_id = storage::get_next_voucher_id(env);
+    let campaign = VoucherCampaign {
+        id: campaign_id,
+        discount,
+        budget,
+        per_user_limit,
+        expires_at,
+        active: true,
+        created_at: now,
+    };
+    storage::save_voucher_campaign(env, &campaign);
+
+    events::voucher_campaign_created(env, &campaign);
+
+    Ok(campaign_id)
+}
+
+pub fn close_voucher_campaign(env: &Env, campaign_id: u64) -> Result<(), VaultError> {
+    // 1. Require admin authorization
+    let admin = storage::get_admin(env).ok_or(VaultError::NotInitialized)?;
+    admin.require_auth();
+
+    // 2. Get campaign and verify it is still active
+    let mut campaign = storage::get_voucher_campaign(env, campaign_id)
+        .ok_or(VaultError::VoucherNotFound)?;
+
+    if !campaign.active {
+        return Err(VaultError::VoucherExpired);
+    }
+
+    // 3. Move the remaining budget into the fee pool
+    let remaining = campaign.budget;
+    storage::set_fee_pool(env, storage::get_fee_pool(env) + remaining);
+    storage::adjust_locked(env, -remaining);
+
+    campaign.budget = 0;
+    campaign.active = false;
+    storage::save_voucher_campaign(env, &campaign);
+
+    events::voucher_campaign_closed(env, campaign_id, remaining);
+
+    Ok(())
+}
+
+pub fn create_retainer(
+    env: &Env,
+    user: &Address,
+    expert: &Address,
+    price_per_period: i128,
+    period: u64,
+    allotment: u64,
+    rollover: bool,
+) -> Result<u64, MarketError> {
+    // 1. Require user authorization
+    user.require_auth();
+
+    // 2. Validate retainer terms
+    if price_per_period <= 0 || period == 0 || allotment == 0 || allotment > period {
+        return Err(MarketError::InvalidAmount);
+    }
+
+    let now = env.ledger().timestamp();
+    let period_end = now.checked_add(period).ok_or(MarketError::InvalidSchedule)?;
+
+    // 3. Escrow the first period through the user's allowance
+    charge_retainer_period(env, user, price_per_period)?;
+
+    // 4. Create retainer
+    let retainer_id = storage::get_next_retainer_id(env);
+    let retainer = RetainerRecord {
+        id: retainer_id,
+        user: user.clone(),
+        expert: expert.clone(),
+        price_per_period,
+        period,
+        allotment,
+        rollover,
+        available_seconds: allotment,
+        escrow: price_per_period,
+        period_end,
+        cancel_at_period_end: false,
+        status: BookingStatus::Pending,
+        created_at: now,
+    };
+    storage::save_retainer(env, &retainer);
+
+    events::retainer_created(env, &retainer);
+    events::retainer_charged(env, &retainer);
+
+    Ok(retainer_id)
+}
+
+pub fn charge_period(env: &Env, retainer_id: u64) -> Result<(), MarketError> {
+    // 1. Get retainer and verify it is still running
+    let mut retainer = storage::get_retainer(env, retainer_id)
+        .ok_or(MarketError::RetainerNotFound)?;
+
+    if retainer.status != BookingStatus::Pending {
+        return Err(MarketError::RetainerNotActive);
+    }
+
+    // 2. The current period must be over (no auth needed; anyone may trigger the charge)
+    if env.ledger().timestamp() < retainer.period_end {
+        return Err(MarketError::PeriodNotElapsed);
+    }
+
+    // 3. Unused time expires unless it rolls over, up to the rollover cap
+    let kept = if retainer.rollover {
+        retainer
+            .available_seconds
+            .min(retainer.allotment * MAX_ROLLOVER_PERIODS)
+    } else {
+        0
+    };
+    let expired = retainer.available_seconds - kept;
+    let mut refund = refund_retainer_seconds(env, &mut retainer, expired)?;
+
+    // 4. A cancelled retainer, or one the user can no longer pay for, ends instead of renewing
+    let lapsed = !can_charge_retainer(env, &retainer.user, retainer.price_per_period);
+    if retainer.cancel_at_period_end || lapsed {
+        let remaining = retainer.available_seconds;
+        refund += refund_retainer_seconds(env, &mut retainer, remaining)?;
+        retainer.status = if lapsed && !retainer.cancel_at_period_end {
+            BookingStatus::Expired
+        } else {
+            BookingStatus::Complete
+        };
+        storage::save_retainer(env, &retainer);
+
+        events::retainer_ended(env, &retainer, refund);
+        return Ok(());
+    }
+
+    // 5. Escrow the next period and grant its allotment
+    charge_retainer_period(env, &retainer.user, retainer.price_per_period)?;
+
+    retainer.available_seconds += retainer.allotment;
+    retainer.escrow += retainer.price_per_period;
+    retainer.period_end = retainer
+        .period_end
+        .checked_add(retainer.period)
+        .ok_or(MarketError::InvalidSchedule)?;
+    storage::save_retainer(env, &retainer);
+
+    events::retainer_charged(env, &retainer);
+
+    Ok(())
+}
+
+pub fn record_retainer_usage(
+    env: &Env,
+    retainer_id: u64,
+    seconds: u64,
+) -> Result<(), MarketError> {
+    // 1. Require oracle authorization
+    let oracle = storage::get_oracle(env);
+    oracle.require_auth();
+
+    // 2. Get retainer and verify it is still running
+    let mut retainer = storage::get_retainer(env, retainer_id)
+        .ok_or(MarketError::RetainerNotFound)?;
+
+    if retainer.status != BookingStatus::Pending {
+        return Err(MarketError::RetainerNotActive);
+    }
+
+    // 3. Usage must fit in the remaining allotment
+    if seconds == 0 {
+        return Err(MarketError::InvalidAmount);
+    }
+    if seconds > retainer.available_seconds {
+        return Err(MarketError::AllotmentExceeded);
+    }
+
+    // 4. Pay the expert for the time used out of the escrow, net of platform fee
+    let amount = retainer_share(&retainer, seconds)?;
+    let fee = platform_fee(env, amount).map_err(|_| MarketError::InvalidAmount)?;
+
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(&contract_address, &retainer.expert, &(amount - fee));
+
+    if fee > 0 {
+        storage::set_fee_pool(env, storage::get_fee_pool(env) + fee);
+    }
+    storage::adjust_locked(env, -amount);
+
+    retainer.available_seconds -= seconds;
+    retainer.escrow -= amount;
+    storage::save_retainer(env, &retainer);
+
+    events::retainer_usage(env, &retainer, seconds, amount);
+
+    Ok(())
+}
+
+pub fn cancel_retainer(env: &Env, caller: &Address, retainer_id: u64) -> Result<(), MarketError> {
+    // 1. Require caller authorization
+    caller.require_auth();
+
+    // 2. Get retainer and verify the caller is one of its parties
+    let mut retainer = storage::get_retainer(env, retainer_id)
+        .ok_or(MarketError::RetainerNotFound)?;
+
+    if retainer.user != *caller && retainer.expert != *caller {
+        return Err(MarketError::NotAuthorized);
+    }
+
+    if retainer.status != BookingStatus::Pending || retainer.cancel_at_period_end {
+        return Err(MarketError::RetainerNotActive);
+    }
+
+    // 3. The paid period runs to its end; charge_period then closes the retainer
+    retainer.cancel_at_period_end = true;
+    storage::save_retainer(env, &retainer);
+
+    events::retainer_cancelled(env, &retainer, caller);
+
+    Ok(())
+}
+
+/// Pulls one period's price from the user's allowance into escrow
+fn charge_retainer_period(env: &Env, user: &Address, amount: i128) -> Result<(), MarketError> {
+    if !can_charge_retainer(env, user, amount) {
+        return Err(MarketError::InsufficientAllowance);
+    }
+
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer_from(&contract_address, user, &contract_address, &amount);
+    storage::adjust_locked(env, amount);
+
+    Ok(())
+}
+
+/// Whether the user's allowance and balance still cover a period's price
+fn can_charge_retainer(env: &Env, user: &Address, amount: i128) -> bool {
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+
+    token_client.allowance(user, &contract_address) >= amount
+        && token_client.balance(user) >= amount
+}
+
+/// Escrow held for `seconds` of the retainer's available time
+fn retainer_share(retainer: &RetainerRecord, seconds: u64) -> Result<i128, MarketError> {
+    if seconds == retainer.available_seconds {
+        return Ok(retainer.escrow);
+    }
+
+    let share = retainer
+        .escrow
+        .checked_mul(seconds as i128)
+        .ok_or(MarketError::InvalidAmount)?;
+
+    Ok(share / retainer.available_seconds as i128)
+}
+
+/// Returns the escrow held for `seconds` of unused time to the user and the amount refunded
+fn refund_retainer_seconds(
+    env: &Env,
+    retainer: &mut RetainerRecord,
+    seconds: u64,
+) -> Result<i128, MarketError> {
+    let amount = retainer_share(retainer, seconds)?;
+
+    if amount > 0 {
+        let token_address = storage::get_token(env);
+        let token_client = token::Client::new(env, &token_address);
+        let contract_address = env.current_contract_address();
+        token_client.transfer(&contract_address, &retainer.user, &amount);
+        storage::adjust_locked(env, -amount);
+    }
+
+    retainer.available_seconds -= seconds;
+    retainer.escrow -= amount;
+
+    Ok(amount)
+}
+
+pub fn create_pool(env: &Env, owner: &Address, period: u64) -> Result<u64, VaultError> {
+    // 1. Require owner authorization
+    owner.require_auth();
+
+    if period == 0 {
+        return Err(VaultError::InvalidSchedule);
+    }
+
+    // 2. Create an empty pool; funds arrive through deposit_to_pool
+    let pool_id = storage::get_next_pool_id(env);
+    let pool = BudgetPool {
+        id: pool_id,
+        owner: owner.clone(),
+        balance: 0,
+        period,
+        created_at: env.ledger().timestamp(),
+    };
+    storage::save_pool(env, &pool);
+
+    events::pool_created(env, &pool);
+
+    Ok(pool_id)
+}
+
+pub fn deposit_to_pool(
+    env: &Env,
+    owner: &Address,
+    pool_id: u64,
+    amount: i128,
+) -> Result<(), VaultError> {
+    // 1. Require owner authorization
+    owner.require_auth();
+
+    if amount <= 0 {
+        return Err(VaultError::InvalidAmount);
+    }
+
+    let mut pool = get_owned_pool(env, owner, pool_id)?;
+
+    // 2. Transfer funds from owner to this contract
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(owner, &contract_address, &amount);
+    storage::adjust_locked(env, amount);
+
+    pool.balance = pool.balance.checked_add(amount).ok_or(VaultError::InvalidAmount)?;
+    storage::save_pool(env, &pool);
+
+    events::pool_deposited(env, &pool, amount);
+
+    Ok(())
+}
+
+pub fn withdraw_from_pool(
+    env: &Env,
+    owner: &Address,
+    pool_id: u64,
+    amount: i128,
+) -> Result<(), VaultError> {
+    // 1. Require owner authorization
+    owner.require_auth();
+
+    if amount <= 0 {
+        return Err(VaultError::InvalidAmount);
+    }
+
+    let mut pool = get_owned_pool(env, owner, pool_id)?;
+
+    // 2. Only unspent funds can leave; deposits of open bookings are not in the balance
+    if amount > pool.balance {
+        return Err(VaultError::InsufficientBalance);
+    }
+
+    pool.balance -= amount;
+    storage::save_pool(env, &pool);
+
+    let token_address = storage::get_token(env);
+    let token_client = token::Client::new(env, &token_address);
+    let contract_address = env.current_contract_address();
+    token_client.transfer(&contract_address, owner, &amount);
+    storage::adjust_locked(env, -amount);
+
+    events::pool_withdrawn(env, &pool, amount);
+
+    Ok(())
+}
+
+pub fn set_pool_beneficiary(
+    env: &Env,
+    owner: &Address,
+    pool_id: u64,
+    beneficiary: &Address,
+    limit_per_period: i128,
+) -> Result<(), VaultError> {
+    // 1. Require owner authorization
+    owner.require_auth();
+
+    if limit_per_period < 0 {
+        return Err(VaultError::InvalidAmount);
+    }
+
+    let pool = get_owned_pool(env, owner, pool_id)?;
+
+    // 2. A zero limit removes the beneficiary; otherwise keep spend already made this period
+    if limit_per_period == 0 {
+        storage::remove_pool_beneficiary(env, pool_id, beneficiary);
+    } else {
+        let period_start = current_pool_period(env, &pool);
+        let spent = match storage::get_pool_beneficiary(env, pool_id, beneficiary) {
+            Some(entry) if entry.period_start == period_start => entry.spent,
+            _ => 0,
+        };
+        let entry = PoolBeneficiary {
+            limit_per_period,
+            spent,
+            period_start,
+        };
+        storage::set_pool_beneficiary(env, pool_id, beneficiary, &entry);
+    }
+
+    events::pool_beneficiary_set(env, &pool, beneficiary, limit_per_period);
+
+    Ok(())
+}
+
+pub fn set_pool_expert(
+    env: &Env,
+    owner: &Address,
+    pool_id: u64,
+    expert: &Address,
+    allowed: bool,
+) -> Result<(), VaultError> {
+    // 1. Require owner authorization
+    owner.require_auth();
+
+    let pool = get_owned_pool(env, owner, pool_id)?;
+
+    // 2. Update the allowlist
+    storage::set_pool_expert(env, pool_id, expert, allowed);
+
+    events::pool_expert_set(env, &pool, expert, allowed);
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn book_session_from_pool(
+    env: &Env,
+    beneficiary: &Address,
+    pool_id: u64,
+    expert: &Address,
+    rate_per_second: i128,
+    max_duration: u64,
+    scheduled_start: u64,
+    grace_period: u64,
+) -> Result<u64, VaultError> {
+    let pricing = flat_pricing(env, rate_per_second)?;
+
+    create_booking(
+        env,
+        beneficiary,
+        expert,
+        pricing,
+        max_duration,
+        scheduled_start,
+        grace_period,
+        None,
+        DepositSource::Pool(pool_id),
+        false,
+    )
+}
+
+/// Loads a pool and checks that the caller owns it
+fn get_owned_pool(env: &Env, owner: &Address, pool_id: u64) -> Result<BudgetPool, VaultError> {
+    let pool = storage::get_pool(env, pool_id).ok_or(VaultError::PoolNotFound)?;
+
+    if pool.owner != *owner {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    Ok(pool)
+}
+
+/// Start of the spending-limit period containing the current ledger time
+fn current_pool_period(env: &Env, pool: &BudgetPool) -> u64 {
+    let elapsed = env.ledger().timestamp() - pool.created_at;
+    pool.created_at + elapsed / pool.period * pool.period
+}
+
+/// Checks the allowlist and the beneficiary's limit, then moves the deposit out of the pool balance
+fn draw_from_pool(
+    env: &Env,
+    pool_id: u64,
+    beneficiary: &Address,
+    expert: &Address,
+    amount: i128,
+) -> Result<(), VaultError> {
+    let mut pool = storage::get_pool(env, pool_id).ok_or(VaultError::PoolNotFound)?;
+
+    let mut entry = storage::get_pool_beneficiary(env, pool_id, beneficiary)
+        .ok_or(VaultError::NotAuthorized)?;
+
+    if !storage::is_pool_expert(env, pool_id, expert) {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    // Spending resets at each period boundary
+    let period_start = current_pool_period(env, &pool);
+    if entry.period_start != period_start {
+        entry.spent = 0;
+        entry.period_start = period_start;
+    }
+
+    let spent = entry.spent.checked_add(amount).ok_or(VaultError::InvalidAmount)?;
+    if spent > entry.limit_per_period {
+        return Err(VaultError::SpendingLimitExceeded);
+    }
+    if amount > pool.balance {
+        return Err(VaultError::InsufficientBalance);
+    }
+
+    entry.spent = spent;
+    storage::set_pool_beneficiary(env, pool_id, beneficiary, &entry);
+
+    pool.balance -= amount;
+    storage::save_pool(env, &pool);
+
+    Ok(())
+}
+
+/// Credits a pool-funded refund back to the pool, and to the beneficiary's limit when the
+/// booking was made in the current period
+fn refund_to_pool(
+    env: &Env,
+    pool_id: u64,
+    booking: &BookingRecord,
+    refund: i128,
+) -> Result<(), VaultError> {
+    if refund <= 0 {
+        return Ok(());
+    }
+
+    let mut pool = storage::get_pool(env, pool_id).ok_or(VaultError::PoolNotFound)?;
+    pool.balance += refund;
+    storage::save_pool(env, &pool);
+
+    if let Some(mut entry) = storage::get_pool_beneficiary(env, pool_id, &booking.user) {
+        if booking.created_at >= entry.period_start {
+            entry.spent = (entry.spent - refund).max(0);
+            storage::set_pool_beneficiary(env, pool_id, &booking.user, &entry);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn book_stream(
+    env: &Env,
+    user: &Address,
+    expert: &Address,
+    rate_per_second: i128,
+    max_duration: u64,
+    scheduled_start: u64,
+) -> Result<u64, VaultError> {
+    let pricing = flat_pricing(env, rate_per_second)?;
+
+    // Streams settle by ledger time, so the window closes with the last billable second
+    create_booking(
+        env,
+        user,
+        expert,
+        pricing,
+        max_duration,
+        scheduled_start,
+        0,
+        None,
+        DepositSource::Transfer,
+        true,
+    )
+}
+
+pub fn withdraw_accrued(env: &Env, expert: &Address, booking_id: u64) -> Result<i128, VaultError> {
+    // 1. Require expert authorization
+    expert.require_auth();
+
+    // 2. Get booking and verify it is a running stream of this expert
+    let mut booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    if booking.expert != *expert {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    if !booking.stream {
+        return Err(VaultError::InvalidBookingMode);
+    }
+
+    // 3. Pay out everything accrued since the last withdrawal
+    let accrued = streamed_amount(env, &booking)?;
+    let due = accrued - booking.withdrawn;
+
+    // A stopped stream is already closed but stays withdrawable until its accrual is out
+    let withdrawable = match booking.stopped_at {
+        Some(_) => due > 0,
+        None => booking.status == BookingStatus::Pending,
+    };
+    if !withdrawable {
+        return Err(VaultError::BookingNotPending);
+    }
+
+    if due > 0 {
+        let fee = platform_fee(env, due)?;
+
+        let token_address = storage::get_token(env);
+        let token_client = token::Client::new(env, &token_address);
+        let contract_address = env.current_contract_address();
+        token_client.transfer(&contract_address, expert, &(due - fee));
+
+        if fee > 0 {
+            storage::set_fee_pool(env, storage::get_fee_pool(env) + fee);
+        }
+
+        // After stop_stream the unpaid accrual is held as earnings rather than deposit
+        if booking.stopped_at.is_some() {
+            storage::adjust_earnings(env, -due);
+        } else {
+            storage::adjust_locked(env, -due);
+        }
+    }
+
+    booking.withdrawn = accrued;
+
+    // 4. A running stream completes once its whole deposit has accrued
+    if booking.stopped_at.is_none() && accrued == booking.total_deposit {
+        booking.status = BookingStatus::Complete;
+        record_completed_session(env, &booking, streamed_seconds(env, &booking), accrued);
+        storage::decrement_open_bookings(env, &booking.user, &booking.expert);
+    }
+    storage::save_booking(env, &booking);
+
+    events::stream_withdrawn(env, &booking, due, accrued);
+
+    Ok(due)
+}
+
+pub fn stop_stream(env: &Env, user: &Address, booking_id: u64) -> Result<(), VaultError> {
+    // 1. Require user authorization
+    user.require_auth();
+
+    // 2. Get booking and verify it is a running stream of this user
+    let mut booking = storage::get_booking(env, booking_id)
+        .ok_or(VaultError::BookingNotFound)?;
+
+    if booking.user != *user {
+        return Err(VaultError::NotAuthorized);
+    }
+
+    if booking.status != BookingStatus::Pending {
+        return Err(VaultError::BookingNotPending);
+    }
+
+    if !booking.stream || booking.stopped_at.is_some() {
+        return Err(VaultError::InvalidBookingMode);
+    }
+
+    // 3. Freeze accrual and refund the unstreamed remainder right away
+    booking.stopped_at = Some(env.ledger().timestamp());
+    let accrued = streamed_amount(env, &booking)?;
+    let refund = booking.total_deposit - accrued;
+
+    refund_booking(env, &booking, refund)?;
+
+    // 4. Whatever accrued but is not yet withdrawn is now owed to the expert
+    let owed = accrued - booking.withdrawn;
+    storage::adjust_locked(env, -owed);
+    storage::adjust_earnings(env, owed);
+
+    // 5. The stream closes now; a stop before any accrual is a cancellation, not a session
+    if accrued > 0 {
+        booking.status = BookingStatus::Complete;
+        record_completed_session(env, &booking, streamed_seconds(env, &booking), accrued);
+    } else {
+        booking.status = BookingStatus::Reclaimed;
+        record_reclaim(env, &booking.expert);
+    }
+    storage::save_booking(env, &booking);
+    storage::decrement_open_bookings(env, &booking.user, &booking.expert);
+
+    events::stream_stopped(env, &booking, accrued, refund);
+
+    Ok(())
+}
+
+/// Gross amount a stream has earned at the current ledger time, or when it was stopped
+fn streamed_amount(env: &Env, booking: &BookingRecord) -> Result<i128, VaultError> {
+    pricing_cost(&booking.pricing, streamed_seconds(env, booking))
+}
+
+/// Seconds a stream has run at the current ledger time, or when it was stopped
+fn streamed_seconds(env: &Env, booking: &BookingRecord) -> u64 {
+    let until = booking.stopped_at.unwrap_or(env.ledger().timestamp());
+    until
+        .saturating_sub(booking.scheduled_start)
+        .min(booking.max_duration)
+}
+
+pub fn get_solvency(env: &Env, token: &Address) -> SolvencyReport {
+    let balance = token::Client::new(env, token).balance(&env.current_contract_address());
+
+    // Only the vault token carries liabilities; anything else held is surplus
+    let locked = storage::get_locked_total(env, token);
+    let earnings = storage::get_earnings_total(env, token);
+    let fees = if *token == storage::get_token(env) {
+        storage::get_fee_pool(env)
+    } else {
+        0
+    };
+
+    SolvencyReport {
+        token: token.clone(),
+        balance,
+        locked,
+        fees,
+        earnings,
+        surplus: balance - locked - fees - earnings,
+    }
+}
+
+pub fn set_accepting_bookings(env: &Env, expert: &Address, accepting: bool) {
+    expert.require_auth();
+
+    storage::set_accepting_bookings(env, expert, accepting);
+
+    events::accepting_bookings_set(env, expert, accepting);
+}
+
+pub fn set_user_blocked(env: &Env, expert: &Address, user: &Address, blocked: bool) {
+    expert.require_auth();
+
+    storage::set_user_blocked(env, expert, user, blocked);
+
+    events::user_blocked_set(env, expert, user, blocked);
+}
@@ -1,5 +1,9 @@
 use soroban_sdk::contracterror;
 
+// Contract error specs hold at most 50 cases, so the vault's errors are split in two enums.
+// Codes are unique across both, and a condition both report keeps the same code in each.
+
+/// Errors of sessions, settlement, vouchers, pools and vault administration
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -11,4 +15,76 @@ pub enum VaultError {
     BookingNotPending = 5,
     InvalidAmount = 6,
     ReclaimTooEarly = 7,
-}
\ No newline at end of file
+    QuorumRequired = 8,
+    QuorumNotRequired = 9,
+    InvalidQuorum = 10,
+    DuplicateReport = 11,
+    BookingNotDisputed = 12,
+    InvalidBillingPolicy = 13,
+    InvalidPricingSchedule = 14,
+    InvalidSchedule = 15,
+    SessionNotStarted = 16,
+    BookingExpired = 17,
+    BookingNotExpired = 18,
+    InvalidFee = 19,
+    InsufficientFees = 20,
+    SigningKeyNotSet = 38,
+    QuoteExpired = 39,
+    QuoteAlreadyUsed = 40,
+    VoucherNotFound = 41,
+    VoucherExpired = 42,
+    VoucherLimitReached = 43,
+    VoucherBudgetExhausted = 44,
+    BookingNotComplete = 45,
+    InsufficientAllowance = 50,
+    SpendingLimitExceeded = 51,
+    InsufficientBalance = 52,
+    PoolNotFound = 53,
+    InvalidBookingMode = 54,
+    NotAcceptingBookings = 55,
+    UserBlocked = 56,
+    TooManyPendingBookings = 57,
+    DepositBelowMinimum = 58,
+    ProposalNotFound = 59,
+}
+
+/// Errors of engagements, questions, bounties, auctions and retainers
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MarketError {
+    NotInitialized = 1,
+    NotAuthorized = 3,
+    BookingNotPending = 5,
+    InvalidAmount = 6,
+    ReclaimTooEarly = 7,
+    InvalidSchedule = 15,
+    BookingExpired = 17,
+    BookingNotExpired = 18,
+    EngagementNotFound = 21,
+    InvalidMilestones = 22,
+    MilestoneAlreadySubmitted = 23,
+    MilestoneNotSubmitted = 24,
+    ApprovalTimeoutNotReached = 25,
+    QuestionNotFound = 26,
+    BountyNotFound = 27,
+    RegistryNotSet = 28,
+    ExpertNotVerified = 29,
+    AlreadySubmitted = 30,
+    BountyHasSubmissions = 31,
+    InvalidAwards = 32,
+    TooManySubmissions = 33,
+    AuctionNotFound = 34,
+    AuctionEnded = 35,
+    AuctionNotEnded = 36,
+    BidTooLow = 37,
+    RetainerNotFound = 46,
+    RetainerNotActive = 47,
+    PeriodNotElapsed = 48,
+    AllotmentExceeded = 49,
+    InsufficientAllowance = 50,
+    NotAcceptingBookings = 55,
+    UserBlocked = 56,
+    TooManyPendingBookings = 57,
+    DepositBelowMinimum = 58,
+}
@@ -1,24 +1,1192 @@
-use soroban_sdk::{Address, Env, symbol_short};
+use crate::types::{
+    AuctionRecord, BookingRecord, BountyRecord, BudgetPool, CounterProposal, EngagementRecord,
+    OptionalRejectionReason, QuestionRecord, RetainerRecord, SettlementQuote, VoucherCampaign,
+};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol};
+
+/// Version of the booking lifecycle event payloads below.
+/// Bumped whenever a field is added, removed or changes meaning, so indexers can decode by version.
+///
+/// - 1: booking, settlement, reclaim, expiry, rejection, dispute, tip and stream events carry a
+///   versioned struct under (name, booking ID, user, expert) topics.
+/// - 2: session_rejected adds `reason` and `message_hash`; counter-proposal and reschedule
+///   events are added under the same topics.
+/// - 3: the remaining lifecycle events (engagements, questions, bounties, auctions, quotes,
+///   vouchers, retainers, pools and duration reports) move from tuples to versioned structs
+///   under (name, record ID, user, expert) topics, with a participant the record lacks published
+///   as void. session_rejected `reason` becomes an `OptionalRejectionReason` instead of a code.
+pub const EVENT_VERSION: u32 = 3;
+
+/// Lifecycle events are published under (name, record ID, user, expert) topics,
+/// so indexers can filter by record or by either participant.
+/// A participant the record does not have is published as void.
+fn lifecycle_topics(
+    name: Symbol,
+    id: u64,
+    user: Option<&Address>,
+    expert: Option<&Address>,
+) -> (Symbol, u64, Option<Address>, Option<Address>) {
+    (name, id, user.cloned(), expert.cloned())
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BookingCreatedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub deposit: i128,
+}
 
 /// Emitted when a new booking is created
-pub fn booking_created(env: &Env, booking_id: u64, user: &Address, expert: &Address, deposit: i128) {
-    let topics = (symbol_short!("booked"), booking_id);
-    env.events().publish(topics, (user.clone(), expert.clone(), deposit));
+#[allow(deprecated)]
+pub fn booking_created(
+    env: &Env,
+    booking_id: u64,
+    user: &Address,
+    expert: &Address,
+    deposit: i128,
+) {
+    let topics = lifecycle_topics(symbol_short!("booked"), booking_id, Some(user), Some(expert));
+    let event = BookingCreatedEvent {
+        version: EVENT_VERSION,
+        booking_id,
+        user: user.clone(),
+        expert: expert.clone(),
+        deposit,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionFinalizedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub actual_duration: u64,
+    pub billable_duration: u64,
+    pub total_cost: i128,           // Expert pay plus platform fee
+    pub expert_pay: i128,
+    pub platform_fee: i128,
+    pub refund: i128,
 }
 
 /// Emitted when a session is finalized
-pub fn session_finalized(env: &Env, booking_id: u64, actual_duration: u64, total_cost: i128) {
-    let topics = (symbol_short!("finalized"), booking_id);
-    env.events().publish(topics, (actual_duration, total_cost));
+#[allow(deprecated)]
+pub fn session_finalized(
+    env: &Env,
+    booking: &BookingRecord,
+    actual_duration: u64,
+    quote: &SettlementQuote,
+) {
+    let topics = lifecycle_topics(
+        symbol_short!("finalized"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = SessionFinalizedEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+        actual_duration,
+        billable_duration: quote.billable_duration,
+        total_cost: quote.expert_pay + quote.platform_fee,
+        expert_pay: quote.expert_pay,
+        platform_fee: quote.platform_fee,
+        refund: quote.refund,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionReclaimedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub refund: i128,
+}
+
+/// Emitted when a user reclaims the deposit of a stale booking or cancelled engagement
+#[allow(deprecated)]
+pub fn session_reclaimed(
+    env: &Env,
+    booking_id: u64,
+    user: &Address,
+    expert: &Address,
+    refund: i128,
+) {
+    let topics = lifecycle_topics(symbol_short!("reclaim"), booking_id, Some(user), Some(expert));
+    let event = SessionReclaimedEvent {
+        version: EVENT_VERSION,
+        booking_id,
+        user: user.clone(),
+        expert: expert.clone(),
+        refund,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BookingExpiredEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub keeper: Address,
+    pub refund: i128,
+    pub keeper_bounty: i128,
+}
+
+/// Emitted when a keeper expires a stale booking and the user is refunded
+#[allow(deprecated)]
+pub fn booking_expired(
+    env: &Env,
+    booking_id: u64,
+    user: &Address,
+    expert: &Address,
+    keeper: &Address,
+    refund: i128,
+    keeper_bounty: i128,
+) {
+    let topics = lifecycle_topics(symbol_short!("expired"), booking_id, Some(user), Some(expert));
+    let event = BookingExpiredEvent {
+        version: EVENT_VERSION,
+        booking_id,
+        user: user.clone(),
+        expert: expert.clone(),
+        keeper: keeper.clone(),
+        refund,
+        keeper_bounty,
+    };
+    env.events().publish(topics, event);
 }
 
-pub fn session_reclaimed(env: &Env, booking_id: u64, amount: i128) {
-    let topics = (symbol_short!("reclaim"), booking_id);
-    env.events().publish(topics, amount);
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionRejectedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub refund: i128,
+    pub reason: OptionalRejectionReason, // Added in version 2
+    pub message_hash: Option<BytesN<32>>, // Added in version 2
 }
 
 /// Emitted when an expert rejects a pending session
-pub fn session_rejected(env: &Env, booking_id: u64, reason: &str) {
-    let topics = (symbol_short!("reject"), booking_id);
-    env.events().publish(topics, reason);
+#[allow(deprecated)]
+pub fn session_rejected(env: &Env, booking: &BookingRecord) {
+    let topics = lifecycle_topics(
+        symbol_short!("reject"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = SessionRejectedEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+        refund: booking.total_deposit,
+        reason: booking.rejection_reason,
+        message_hash: booking.rejection_hash.clone(),
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CounterProposedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub rate_per_second: i128,
+    pub max_duration: u64,
+    pub scheduled_start: u64,
+    pub total_deposit: i128,
+}
+
+/// Emitted when an expert proposes revised terms for a pending booking
+#[allow(deprecated)]
+pub fn counter_proposed(env: &Env, booking: &BookingRecord, proposal: &CounterProposal) {
+    let topics = lifecycle_topics(
+        symbol_short!("c_propose"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = CounterProposedEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+        rate_per_second: proposal.rate_per_second,
+        max_duration: proposal.max_duration,
+        scheduled_start: proposal.scheduled_start,
+        total_deposit: proposal.total_deposit,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CounterAcceptedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub total_deposit: i128,
+    pub deposit_change: i128,       // Positive when the user paid in, negative when refunded
+}
+
+/// Emitted when a user accepts a counter-proposal and the escrow is adjusted
+#[allow(deprecated)]
+pub fn counter_accepted(env: &Env, booking: &BookingRecord, deposit_change: i128) {
+    let topics = lifecycle_topics(
+        symbol_short!("c_accept"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = CounterAcceptedEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+        total_deposit: booking.total_deposit,
+        deposit_change,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RescheduleProposedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub proposer: Address,
+    pub new_start: u64,
+}
+
+/// Emitted when either party proposes a new start time for a pending booking
+#[allow(deprecated)]
+pub fn reschedule_proposed(env: &Env, booking: &BookingRecord, proposer: &Address, new_start: u64) {
+    let topics = lifecycle_topics(
+        symbol_short!("rs_prop"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = RescheduleProposedEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+        proposer: proposer.clone(),
+        new_start,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RescheduleAcceptedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub previous_start: u64,
+    pub new_start: u64,
+    pub expires_at: u64,
+}
+
+/// Emitted when the other party accepts a reschedule and the booking moves
+#[allow(deprecated)]
+pub fn reschedule_accepted(env: &Env, booking: &BookingRecord, previous_start: u64) {
+    let topics = lifecycle_topics(
+        symbol_short!("rs_accept"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = RescheduleAcceptedEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+        previous_start,
+        new_start: booking.scheduled_start,
+        expires_at: booking.expires_at,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DurationReportedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub oracle: Address,
+    pub actual_duration: u64,
+}
+
+/// Emitted when an oracle from the quorum set reports a session duration
+#[allow(deprecated)]
+pub fn duration_reported(
+    env: &Env,
+    booking: &BookingRecord,
+    oracle: &Address,
+    actual_duration: u64,
+) {
+    let topics = lifecycle_topics(
+        symbol_short!("reported"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = DurationReportedEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+        oracle: oracle.clone(),
+        actual_duration,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionDisputedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+}
+
+/// Emitted when oracle reports for a booking disagree
+#[allow(deprecated)]
+pub fn session_disputed(env: &Env, booking: &BookingRecord) {
+    let topics = lifecycle_topics(
+        symbol_short!("disputed"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = SessionDisputedEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+    };
+    env.events().publish(topics, event);
+}
+
+/// Emitted when the admin withdraws accrued platform fees
+#[allow(deprecated)]
+pub fn fees_withdrawn(env: &Env, to: &Address, amount: i128) {
+    let topics = (symbol_short!("fees_out"),);
+    env.events().publish(topics, (to.clone(), amount));
+}
+
+/// Emitted when the admin sweeps stray tokens held above the vault's liabilities
+#[allow(deprecated)]
+pub fn tokens_swept(env: &Env, token: &Address, admin: &Address, to: &Address, amount: i128) {
+    let topics = (symbol_short!("swept"), token.clone());
+    env.events().publish(topics, (admin.clone(), to.clone(), amount));
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneSubmittedEvent {
+    pub version: u32,
+    pub engagement_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub milestone: u32,
+}
+
+/// Emitted when an expert submits a milestone for approval
+#[allow(deprecated)]
+pub fn milestone_submitted(env: &Env, engagement: &EngagementRecord) {
+    let topics = lifecycle_topics(
+        symbol_short!("ms_submit"),
+        engagement.id,
+        Some(&engagement.user),
+        Some(&engagement.expert),
+    );
+    let event = MilestoneSubmittedEvent {
+        version: EVENT_VERSION,
+        engagement_id: engagement.id,
+        user: engagement.user.clone(),
+        expert: engagement.expert.clone(),
+        milestone: engagement.next_milestone,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MilestoneReleasedEvent {
+    pub version: u32,
+    pub engagement_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub milestone: u32,
+    pub amount: i128,               // Gross amount released, before the platform fee
+    pub auto: bool,                 // Released by the approval timeout rather than the user
+}
+
+/// Emitted when a milestone is approved and paid out
+#[allow(deprecated)]
+pub fn milestone_released(
+    env: &Env,
+    engagement: &EngagementRecord,
+    milestone: u32,
+    amount: i128,
+    auto: bool,
+) {
+    let topics = lifecycle_topics(
+        symbol_short!("ms_paid"),
+        engagement.id,
+        Some(&engagement.user),
+        Some(&engagement.expert),
+    );
+    let event = MilestoneReleasedEvent {
+        version: EVENT_VERSION,
+        engagement_id: engagement.id,
+        user: engagement.user.clone(),
+        expert: engagement.expert.clone(),
+        milestone,
+        amount,
+        auto,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuestionAnsweredEvent {
+    pub version: u32,
+    pub question_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub answer_hash: BytesN<32>,
+    pub fee: i128,
+}
+
+/// Emitted when an expert answers a question and is paid
+#[allow(deprecated)]
+pub fn question_answered(env: &Env, question: &QuestionRecord, answer_hash: &BytesN<32>) {
+    let topics = lifecycle_topics(
+        symbol_short!("answered"),
+        question.id,
+        Some(&question.user),
+        Some(&question.expert),
+    );
+    let event = QuestionAnsweredEvent {
+        version: EVENT_VERSION,
+        question_id: question.id,
+        user: question.user.clone(),
+        expert: question.expert.clone(),
+        answer_hash: answer_hash.clone(),
+        fee: question.fee,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BountyPostedEvent {
+    pub version: u32,
+    pub bounty_id: u64,
+    pub poster: Address,
+    pub amount: i128,
+    pub expires_at: u64,
+}
+
+/// Emitted when a user posts an open bounty
+#[allow(deprecated)]
+pub fn bounty_posted(env: &Env, bounty: &BountyRecord) {
+    let topics = lifecycle_topics(symbol_short!("bounty"), bounty.id, Some(&bounty.poster), None);
+    let event = BountyPostedEvent {
+        version: EVENT_VERSION,
+        bounty_id: bounty.id,
+        poster: bounty.poster.clone(),
+        amount: bounty.amount,
+        expires_at: bounty.expires_at,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BountyAnsweredEvent {
+    pub version: u32,
+    pub bounty_id: u64,
+    pub poster: Address,
+    pub expert: Address,
+    pub submission: u32,
+}
+
+/// Emitted when a verified expert answers a bounty
+#[allow(deprecated)]
+pub fn bounty_answered(env: &Env, bounty: &BountyRecord, expert: &Address, submission: u32) {
+    let topics = lifecycle_topics(
+        symbol_short!("b_answer"),
+        bounty.id,
+        Some(&bounty.poster),
+        Some(expert),
+    );
+    let event = BountyAnsweredEvent {
+        version: EVENT_VERSION,
+        bounty_id: bounty.id,
+        poster: bounty.poster.clone(),
+        expert: expert.clone(),
+        submission,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BountyAwardedEvent {
+    pub version: u32,
+    pub bounty_id: u64,
+    pub poster: Address,
+    pub expert: Address,
+    pub amount: i128,               // Gross award, before the platform fee
+}
+
+/// Emitted for each winning submission when a bounty is awarded
+#[allow(deprecated)]
+pub fn bounty_awarded(env: &Env, bounty: &BountyRecord, expert: &Address, amount: i128) {
+    let topics = lifecycle_topics(
+        symbol_short!("b_award"),
+        bounty.id,
+        Some(&bounty.poster),
+        Some(expert),
+    );
+    let event = BountyAwardedEvent {
+        version: EVENT_VERSION,
+        bounty_id: bounty.id,
+        poster: bounty.poster.clone(),
+        expert: expert.clone(),
+        amount,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BountyReclaimedEvent {
+    pub version: u32,
+    pub bounty_id: u64,
+    pub poster: Address,
+    pub amount: i128,
+}
+
+/// Emitted when an unanswered bounty is reclaimed by its poster
+#[allow(deprecated)]
+pub fn bounty_reclaimed(env: &Env, bounty: &BountyRecord) {
+    let topics =
+        lifecycle_topics(symbol_short!("b_reclaim"), bounty.id, Some(&bounty.poster), None);
+    let event = BountyReclaimedEvent {
+        version: EVENT_VERSION,
+        bounty_id: bounty.id,
+        poster: bounty.poster.clone(),
+        amount: bounty.amount,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionCreatedEvent {
+    pub version: u32,
+    pub auction_id: u64,
+    pub expert: Address,
+    pub reserve_price: i128,
+    pub end_time: u64,
+}
+
+/// Emitted when an expert opens an auction for a time slot
+#[allow(deprecated)]
+pub fn auction_created(env: &Env, auction: &AuctionRecord) {
+    let topics =
+        lifecycle_topics(symbol_short!("auction"), auction.id, None, Some(&auction.expert));
+    let event = AuctionCreatedEvent {
+        version: EVENT_VERSION,
+        auction_id: auction.id,
+        expert: auction.expert.clone(),
+        reserve_price: auction.reserve_price,
+        end_time: auction.end_time,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidPlacedEvent {
+    pub version: u32,
+    pub auction_id: u64,
+    pub bidder: Address,
+    pub expert: Address,
+    pub amount: i128,
+}
+
+/// Emitted when a new highest bid is placed
+#[allow(deprecated)]
+pub fn bid_placed(env: &Env, auction: &AuctionRecord, bidder: &Address, amount: i128) {
+    let topics = lifecycle_topics(
+        symbol_short!("bid"),
+        auction.id,
+        Some(bidder),
+        Some(&auction.expert),
+    );
+    let event = BidPlacedEvent {
+        version: EVENT_VERSION,
+        auction_id: auction.id,
+        bidder: bidder.clone(),
+        expert: auction.expert.clone(),
+        amount,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionSettledEvent {
+    pub version: u32,
+    pub auction_id: u64,
+    pub winner: Option<Address>,
+    pub expert: Address,
+    pub booking_id: Option<u64>,    // Winner's booking; None if there was no bid
+    pub amount: i128,
+}
+
+/// Emitted when an auction is settled, with the winner's booking if there was a bid
+#[allow(deprecated)]
+pub fn auction_settled(env: &Env, auction: &AuctionRecord) {
+    let topics = lifecycle_topics(
+        symbol_short!("a_settle"),
+        auction.id,
+        auction.highest_bidder.as_ref(),
+        Some(&auction.expert),
+    );
+    let event = AuctionSettledEvent {
+        version: EVENT_VERSION,
+        auction_id: auction.id,
+        winner: auction.highest_bidder.clone(),
+        expert: auction.expert.clone(),
+        booking_id: auction.booking_id,
+        amount: auction.highest_bid,
+    };
+    env.events().publish(topics, event);
+}
+
+/// Emitted when an expert registers the key used to sign quotes
+#[allow(deprecated)]
+pub fn signing_key_set(env: &Env, expert: &Address, public_key: &BytesN<32>) {
+    let topics = (symbol_short!("key_set"), expert.clone());
+    env.events().publish(topics, public_key.clone());
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuoteRedeemedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub nonce: u64,
+}
+
+/// Emitted when a signed quote is redeemed for a booking
+#[allow(deprecated)]
+pub fn quote_redeemed(env: &Env, booking_id: u64, user: &Address, expert: &Address, nonce: u64) {
+    let topics =
+        lifecycle_topics(symbol_short!("quote"), booking_id, Some(user), Some(expert));
+    let event = QuoteRedeemedEvent {
+        version: EVENT_VERSION,
+        booking_id,
+        user: user.clone(),
+        expert: expert.clone(),
+        nonce,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoucherCampaignCreatedEvent {
+    pub version: u32,
+    pub campaign_id: u64,
+    pub budget: i128,
+    pub expires_at: u64,
+}
+
+/// Emitted when the admin opens a voucher campaign
+#[allow(deprecated)]
+pub fn voucher_campaign_created(env: &Env, campaign: &VoucherCampaign) {
+    let topics = lifecycle_topics(symbol_short!("voucher"), campaign.id, None, None);
+    let event = VoucherCampaignCreatedEvent {
+        version: EVENT_VERSION,
+        campaign_id: campaign.id,
+        budget: campaign.budget,
+        expires_at: campaign.expires_at,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoucherRedeemedEvent {
+    pub version: u32,
+    pub campaign_id: u64,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub subsidy: i128,
+}
+
+/// Emitted when a voucher subsidizes a booking
+#[allow(deprecated)]
+pub fn voucher_redeemed(
+    env: &Env,
+    campaign_id: u64,
+    booking_id: u64,
+    user: &Address,
+    expert: &Address,
+    subsidy: i128,
+) {
+    let topics =
+        lifecycle_topics(symbol_short!("v_redeem"), campaign_id, Some(user), Some(expert));
+    let event = VoucherRedeemedEvent {
+        version: EVENT_VERSION,
+        campaign_id,
+        booking_id,
+        user: user.clone(),
+        expert: expert.clone(),
+        subsidy,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoucherCampaignClosedEvent {
+    pub version: u32,
+    pub campaign_id: u64,
+    pub remaining: i128,            // Unspent budget moved to the fee pool
+}
+
+/// Emitted when the admin closes a campaign and its remaining budget returns to the fee pool
+#[allow(deprecated)]
+pub fn voucher_campaign_closed(env: &Env, campaign_id: u64, remaining: i128) {
+    let topics = lifecycle_topics(symbol_short!("v_close"), campaign_id, None, None);
+    let event = VoucherCampaignClosedEvent {
+        version: EVENT_VERSION,
+        campaign_id,
+        remaining,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionTippedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub amount: i128,
+}
+
+/// Emitted when a user tips the expert of a completed session
+#[allow(deprecated)]
+pub fn session_tipped(env: &Env, booking: &BookingRecord, amount: i128) {
+    let topics = lifecycle_topics(
+        symbol_short!("tip"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = SessionTippedEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+        amount,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetainerCreatedEvent {
+    pub version: u32,
+    pub retainer_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub price_per_period: i128,
+    pub period: u64,
+    pub allotment: u64,
+}
+
+/// Emitted when a user puts an expert on retainer
+#[allow(deprecated)]
+pub fn retainer_created(env: &Env, retainer: &RetainerRecord) {
+    let topics = lifecycle_topics(
+        symbol_short!("retainer"),
+        retainer.id,
+        Some(&retainer.user),
+        Some(&retainer.expert),
+    );
+    let event = RetainerCreatedEvent {
+        version: EVENT_VERSION,
+        retainer_id: retainer.id,
+        user: retainer.user.clone(),
+        expert: retainer.expert.clone(),
+        price_per_period: retainer.price_per_period,
+        period: retainer.period,
+        allotment: retainer.allotment,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetainerChargedEvent {
+    pub version: u32,
+    pub retainer_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub amount: i128,
+    pub period_end: u64,
+}
+
+/// Emitted when a retainer period is charged
+#[allow(deprecated)]
+pub fn retainer_charged(env: &Env, retainer: &RetainerRecord) {
+    let topics = lifecycle_topics(
+        symbol_short!("r_charge"),
+        retainer.id,
+        Some(&retainer.user),
+        Some(&retainer.expert),
+    );
+    let event = RetainerChargedEvent {
+        version: EVENT_VERSION,
+        retainer_id: retainer.id,
+        user: retainer.user.clone(),
+        expert: retainer.expert.clone(),
+        amount: retainer.price_per_period,
+        period_end: retainer.period_end,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetainerUsageEvent {
+    pub version: u32,
+    pub retainer_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub seconds: u64,
+    pub amount: i128,               // Gross pay for the seconds, before the platform fee
+    pub available_seconds: u64,
+}
+
+/// Emitted when the oracle records time used against a retainer
+#[allow(deprecated)]
+pub fn retainer_usage(env: &Env, retainer: &RetainerRecord, seconds: u64, amount: i128) {
+    let topics = lifecycle_topics(
+        symbol_short!("r_usage"),
+        retainer.id,
+        Some(&retainer.user),
+        Some(&retainer.expert),
+    );
+    let event = RetainerUsageEvent {
+        version: EVENT_VERSION,
+        retainer_id: retainer.id,
+        user: retainer.user.clone(),
+        expert: retainer.expert.clone(),
+        seconds,
+        amount,
+        available_seconds: retainer.available_seconds,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetainerCancelledEvent {
+    pub version: u32,
+    pub retainer_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub caller: Address,
+    pub period_end: u64,
+}
+
+/// Emitted when either side asks to end a retainer at the current period end
+#[allow(deprecated)]
+pub fn retainer_cancelled(env: &Env, retainer: &RetainerRecord, caller: &Address) {
+    let topics = lifecycle_topics(
+        symbol_short!("r_cancel"),
+        retainer.id,
+        Some(&retainer.user),
+        Some(&retainer.expert),
+    );
+    let event = RetainerCancelledEvent {
+        version: EVENT_VERSION,
+        retainer_id: retainer.id,
+        user: retainer.user.clone(),
+        expert: retainer.expert.clone(),
+        caller: caller.clone(),
+        period_end: retainer.period_end,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetainerEndedEvent {
+    pub version: u32,
+    pub retainer_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub refund: i128,
+}
+
+/// Emitted when a cancelled or lapsed retainer reaches its period end and unused time is refunded
+#[allow(deprecated)]
+pub fn retainer_ended(env: &Env, retainer: &RetainerRecord, refund: i128) {
+    let topics = lifecycle_topics(
+        symbol_short!("r_end"),
+        retainer.id,
+        Some(&retainer.user),
+        Some(&retainer.expert),
+    );
+    let event = RetainerEndedEvent {
+        version: EVENT_VERSION,
+        retainer_id: retainer.id,
+        user: retainer.user.clone(),
+        expert: retainer.expert.clone(),
+        refund,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolCreatedEvent {
+    pub version: u32,
+    pub pool_id: u64,
+    pub owner: Address,
+    pub period: u64,
+}
+
+/// Emitted when an owner opens a budget pool
+#[allow(deprecated)]
+pub fn pool_created(env: &Env, pool: &BudgetPool) {
+    let topics = lifecycle_topics(symbol_short!("pool"), pool.id, Some(&pool.owner), None);
+    let event = PoolCreatedEvent {
+        version: EVENT_VERSION,
+        pool_id: pool.id,
+        owner: pool.owner.clone(),
+        period: pool.period,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolDepositedEvent {
+    pub version: u32,
+    pub pool_id: u64,
+    pub owner: Address,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+/// Emitted when the owner adds funds to a pool
+#[allow(deprecated)]
+pub fn pool_deposited(env: &Env, pool: &BudgetPool, amount: i128) {
+    let topics = lifecycle_topics(symbol_short!("p_dep"), pool.id, Some(&pool.owner), None);
+    let event = PoolDepositedEvent {
+        version: EVENT_VERSION,
+        pool_id: pool.id,
+        owner: pool.owner.clone(),
+        amount,
+        balance: pool.balance,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolWithdrawnEvent {
+    pub version: u32,
+    pub pool_id: u64,
+    pub owner: Address,
+    pub amount: i128,
+    pub balance: i128,
+}
+
+/// Emitted when the owner withdraws unspent funds from a pool
+#[allow(deprecated)]
+pub fn pool_withdrawn(env: &Env, pool: &BudgetPool, amount: i128) {
+    let topics = lifecycle_topics(symbol_short!("p_wd"), pool.id, Some(&pool.owner), None);
+    let event = PoolWithdrawnEvent {
+        version: EVENT_VERSION,
+        pool_id: pool.id,
+        owner: pool.owner.clone(),
+        amount,
+        balance: pool.balance,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolBeneficiarySetEvent {
+    pub version: u32,
+    pub pool_id: u64,
+    pub owner: Address,
+    pub beneficiary: Address,
+    pub limit_per_period: i128,     // Zero when the beneficiary is removed
+}
+
+/// Emitted when a beneficiary's limit is set; a zero limit removes the beneficiary
+#[allow(deprecated)]
+pub fn pool_beneficiary_set(
+    env: &Env,
+    pool: &BudgetPool,
+    beneficiary: &Address,
+    limit_per_period: i128,
+) {
+    let topics = lifecycle_topics(symbol_short!("p_benef"), pool.id, Some(beneficiary), None);
+    let event = PoolBeneficiarySetEvent {
+        version: EVENT_VERSION,
+        pool_id: pool.id,
+        owner: pool.owner.clone(),
+        beneficiary: beneficiary.clone(),
+        limit_per_period,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolExpertSetEvent {
+    pub version: u32,
+    pub pool_id: u64,
+    pub owner: Address,
+    pub expert: Address,
+    pub allowed: bool,
+}
+
+/// Emitted when an expert is added to or removed from a pool's allowlist
+#[allow(deprecated)]
+pub fn pool_expert_set(env: &Env, pool: &BudgetPool, expert: &Address, allowed: bool) {
+    let topics = lifecycle_topics(symbol_short!("p_expert"), pool.id, None, Some(expert));
+    let event = PoolExpertSetEvent {
+        version: EVENT_VERSION,
+        pool_id: pool.id,
+        owner: pool.owner.clone(),
+        expert: expert.clone(),
+        allowed,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamWithdrawnEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub amount: i128,               // Gross amount of this withdrawal
+    pub total_accrued: i128,
+}
+
+/// Emitted when the expert withdraws pay accrued by a streaming booking
+#[allow(deprecated)]
+pub fn stream_withdrawn(env: &Env, booking: &BookingRecord, amount: i128, total_accrued: i128) {
+    let topics = lifecycle_topics(
+        symbol_short!("s_withdr"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = StreamWithdrawnEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+        amount,
+        total_accrued,
+    };
+    env.events().publish(topics, event);
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamStoppedEvent {
+    pub version: u32,
+    pub booking_id: u64,
+    pub user: Address,
+    pub expert: Address,
+    pub total_accrued: i128,
+    pub refund: i128,
+}
+
+/// Emitted when the user stops a stream and receives the unstreamed remainder
+#[allow(deprecated)]
+pub fn stream_stopped(env: &Env, booking: &BookingRecord, total_accrued: i128, refund: i128) {
+    let topics = lifecycle_topics(
+        symbol_short!("s_stop"),
+        booking.id,
+        Some(&booking.user),
+        Some(&booking.expert),
+    );
+    let event = StreamStoppedEvent {
+        version: EVENT_VERSION,
+        booking_id: booking.id,
+        user: booking.user.clone(),
+        expert: booking.expert.clone(),
+        total_accrued,
+        refund,
+    };
+    env.events().publish(topics, event);
+}
+
+/// Emitted when an expert starts or stops accepting new bookings
+#[allow(deprecated)]
+pub fn accepting_bookings_set(env: &Env, expert: &Address, accepting: bool) {
+    let topics = (symbol_short!("accepting"), expert.clone());
+    env.events().publish(topics, accepting);
+}
+
+/// Emitted when an expert adds a user to or removes a user from their blocklist
+#[allow(deprecated)]
+pub fn user_blocked_set(env: &Env, expert: &Address, user: &Address, blocked: bool) {
+    let topics = (symbol_short!("blocked"), expert.clone());
+    env.events().publish(topics, (user.clone(), blocked));
 }
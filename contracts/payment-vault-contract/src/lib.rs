@@ -3,14 +3,21 @@
 mod contract;
 mod error;
 mod events;
+mod registry;
 mod storage;
 mod types;
 #[cfg(test)]
 mod test;
 
-use soroban_sdk::{contract, contractimpl, Address, Env, Vec};
-use crate::error::VaultError;
-use crate::types::BookingRecord;
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Vec};
+use crate::error::{MarketError, VaultError};
+use crate::types::{
+    AuctionRecord, BillingPolicy, BookingLimits, BookingRecord, BountyAward, BountyRecord,
+    BountySubmission, BudgetPool, CounterProposal, DurationReport, EngagementRecord, ExpertStats,
+    OracleQuorum, PoolBeneficiary, PricingTier, QuestionRecord, Quote, RejectionReason,
+    RescheduleProposal, RescheduleRecord, RetainerRecord, SettlementQuote, SolvencyReport,
+    UserStats, VoucherCampaign, VoucherDiscount,
+};
 
 #[contract]
 pub struct PaymentVaultContract;
@@ -29,14 +36,125 @@ impl PaymentVaultContract {
 
     /// Book a session with an expert
     /// User deposits tokens upfront based on rate_per_second * max_duration
+    /// The session can be finalized from scheduled_start until max_duration + grace_period later
+    /// An optional voucher campaign covers part of the deposit
+    #[allow(clippy::too_many_arguments)]
     pub fn book_session(
         env: Env,
         user: Address,
         expert: Address,
         rate_per_second: i128,
         max_duration: u64,
+        scheduled_start: u64,
+        grace_period: u64,
+        voucher_id: Option<u64>,
     ) -> Result<u64, VaultError> {
-        contract::book_session(&env, &user, &expert, rate_per_second, max_duration)
+        contract::book_session(
+            &env,
+            &user,
+            &expert,
+            rate_per_second,
+            max_duration,
+            scheduled_start,
+            grace_period,
+            voucher_id,
+        )
+    }
+
+    /// Book a session funded from the allowance the user granted the vault
+    /// Only the user's authorization of this call is needed, so a relayer can submit it
+    #[allow(clippy::too_many_arguments)]
+    pub fn book_session_for(
+        env: Env,
+        user: Address,
+        expert: Address,
+        rate_per_second: i128,
+        max_duration: u64,
+        scheduled_start: u64,
+        grace_period: u64,
+        voucher_id: Option<u64>,
+    ) -> Result<u64, VaultError> {
+        contract::book_session_for(
+            &env,
+            &user,
+            &expert,
+            rate_per_second,
+            max_duration,
+            scheduled_start,
+            grace_period,
+            voucher_id,
+        )
+    }
+
+    /// Book a streaming session: pay accrues by ledger time from scheduled_start
+    /// and settles through withdraw_accrued and stop_stream instead of the oracle
+    pub fn book_stream(
+        env: Env,
+        user: Address,
+        expert: Address,
+        rate_per_second: i128,
+        max_duration: u64,
+        scheduled_start: u64,
+    ) -> Result<u64, VaultError> {
+        contract::book_stream(&env, &user, &expert, rate_per_second, max_duration, scheduled_start)
+    }
+
+    /// Withdraw pay accrued by a streaming booking; returns the gross amount (Expert-only)
+    pub fn withdraw_accrued(
+        env: Env,
+        expert: Address,
+        booking_id: u64,
+    ) -> Result<i128, VaultError> {
+        contract::withdraw_accrued(&env, &expert, booking_id)
+    }
+
+    /// Stop a streaming booking and refund the unstreamed remainder (User-only)
+    pub fn stop_stream(env: Env, user: Address, booking_id: u64) -> Result<(), VaultError> {
+        contract::stop_stream(&env, &user, booking_id)
+    }
+
+    /// Book a session priced by a tiered schedule
+    /// User deposits the schedule's cost over max_duration; tiers must start at threshold 0
+    pub fn book_session_tiered(
+        env: Env,
+        user: Address,
+        expert: Address,
+        pricing: Vec<PricingTier>,
+        max_duration: u64,
+        scheduled_start: u64,
+        grace_period: u64,
+    ) -> Result<u64, VaultError> {
+        contract::book_session_tiered(
+            &env,
+            &user,
+            &expert,
+            pricing,
+            max_duration,
+            scheduled_start,
+            grace_period,
+        )
+    }
+
+    /// Register the ed25519 public key used to sign quotes (Expert-only)
+    pub fn register_signing_key(
+        env: Env,
+        expert: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), VaultError> {
+        contract::register_signing_key(&env, &expert, public_key)
+    }
+
+    /// Book a session at the terms of an expert-signed quote (User-only)
+    /// The signature covers this vault's address and the quote; each nonce is redeemable once
+    pub fn book_with_quote(
+        env: Env,
+        user: Address,
+        quote: Quote,
+        signature: BytesN<64>,
+        scheduled_start: u64,
+        grace_period: u64,
+    ) -> Result<u64, VaultError> {
+        contract::book_with_quote(&env, &user, quote, signature, scheduled_start, grace_period)
     }
 
     /// Finalize a session (Oracle-only)
@@ -49,8 +167,49 @@ impl PaymentVaultContract {
         contract::finalize_session(&env, booking_id, actual_duration)
     }
 
+    /// Set the billing increment, minimum charge and rounding mode (Expert-only)
+    /// The policy is copied onto each new booking with this expert
+    pub fn set_billing_policy(
+        env: Env,
+        expert: Address,
+        policy: BillingPolicy,
+    ) -> Result<(), VaultError> {
+        contract::set_billing_policy(&env, &expert, policy)
+    }
+
+    /// Configure the M-of-N oracle set for high-value bookings (Admin-only)
+    /// Bookings with a deposit of at least min_deposit need threshold matching reports
+    pub fn set_oracle_quorum(
+        env: Env,
+        oracles: Vec<Address>,
+        threshold: u32,
+        min_deposit: i128,
+    ) -> Result<(), VaultError> {
+        contract::set_oracle_quorum(&env, oracles, threshold, min_deposit)
+    }
+
+    /// Submit a duration report for a high-value booking (Oracle set member only)
+    /// Settles once enough matching reports arrive; a conflicting report disputes the booking
+    pub fn submit_duration_report(
+        env: Env,
+        oracle: Address,
+        booking_id: u64,
+        actual_duration: u64,
+    ) -> Result<(), VaultError> {
+        contract::submit_duration_report(&env, &oracle, booking_id, actual_duration)
+    }
+
+    /// Settle a disputed booking with the given duration (Admin-only)
+    pub fn resolve_dispute(
+        env: Env,
+        booking_id: u64,
+        actual_duration: u64,
+    ) -> Result<(), VaultError> {
+        contract::resolve_dispute(&env, booking_id, actual_duration)
+    }
+
     /// Reclaim funds from a stale booking (User-only)
-    /// Users can reclaim their deposit if the booking has been pending for more than 24 hours
+    /// Users can reclaim their deposit once the booking's finalization window has passed
     pub fn reclaim_stale_session(
         env: Env,
         user: Address,
@@ -59,22 +218,430 @@ impl PaymentVaultContract {
         contract::reclaim_stale_session(&env, &user, booking_id)
     }
 
+    /// Expire a booking whose finalization window has passed (permissionless)
+    /// Refunds the user and pays the caller the keeper bounty from the fee pool
+    pub fn expire_booking(
+        env: Env,
+        keeper: Address,
+        booking_id: u64,
+    ) -> Result<(), VaultError> {
+        contract::expire_booking(&env, &keeper, booking_id)
+    }
+
+    /// Set the platform fee taken from expert payouts, in basis points (Admin-only)
+    pub fn set_platform_fee(env: Env, fee_bps: u32) -> Result<(), VaultError> {
+        contract::set_platform_fee(&env, fee_bps)
+    }
+
+    /// Set the bounty paid to keepers for expiring stale bookings (Admin-only)
+    pub fn set_keeper_bounty(env: Env, amount: i128) -> Result<(), VaultError> {
+        contract::set_keeper_bounty(&env, amount)
+    }
+
+    /// Cap open bookings per user and per user-expert pair, and set a minimum deposit
+    /// (Admin-only); zero disables a cap
+    pub fn set_booking_limits(
+        env: Env,
+        max_pending_per_user: u32,
+        max_pending_per_pair: u32,
+        min_deposit: i128,
+    ) -> Result<(), VaultError> {
+        contract::set_booking_limits(&env, max_pending_per_user, max_pending_per_pair, min_deposit)
+    }
+
+    /// Withdraw accrued platform fees (Admin-only)
+    pub fn withdraw_fees(env: Env, to: Address, amount: i128) -> Result<(), VaultError> {
+        contract::withdraw_fees(&env, &to, amount)
+    }
+
+    /// Send tokens held above the vault's recorded liabilities to `to` (Admin-only)
+    /// Escrowed deposits, fees and earnings cannot be swept; returns the amount sent
+    pub fn sweep(env: Env, token: Address, to: Address) -> Result<i128, VaultError> {
+        contract::sweep(&env, &token, &to)
+    }
+
+    /// Tip the expert of a completed session (User-only)
+    pub fn tip(env: Env, user: Address, booking_id: u64, amount: i128) -> Result<(), VaultError> {
+        contract::tip(&env, &user, booking_id, amount)
+    }
+
+    /// Start or stop accepting new bookings, e.g. while on vacation (Expert-only)
+    pub fn set_accepting_bookings(env: Env, expert: Address, accepting: bool) {
+        contract::set_accepting_bookings(&env, &expert, accepting)
+    }
+
+    /// Add a user to or remove a user from the expert's blocklist (Expert-only)
+    /// Blocked users cannot create new bookings with the expert
+    pub fn set_user_blocked(env: Env, expert: Address, user: Address, blocked: bool) {
+        contract::set_user_blocked(&env, &expert, &user, blocked)
+    }
+
+    /// Put an expert on retainer; each period's price is pulled through the user's token allowance
+    /// and held in escrow until the time is used
+    pub fn create_retainer(
+        env: Env,
+        user: Address,
+        expert: Address,
+        price_per_period: i128,
+        period: u64,
+        allotment: u64,
+        rollover: bool,
+    ) -> Result<u64, MarketError> {
+        contract::create_retainer(
+            &env,
+            &user,
+            &expert,
+            price_per_period,
+            period,
+            allotment,
+            rollover,
+        )
+    }
+
+    /// Charge the next retainer period once the current one is over (Permissionless)
+    /// Unused time is refunded unless it rolls over; a cancelled retainer, or one whose allowance
+    /// has lapsed, ends instead and refunds everything still in escrow
+    pub fn charge_period(env: Env, retainer_id: u64) -> Result<(), MarketError> {
+        contract::charge_period(&env, retainer_id)
+    }
+
+    /// Record seconds of expert time used against a retainer (Oracle-only)
+    /// The expert is paid for that time out of the retainer's escrow
+    pub fn record_retainer_usage(
+        env: Env,
+        retainer_id: u64,
+        seconds: u64,
+    ) -> Result<(), MarketError> {
+        contract::record_retainer_usage(&env, retainer_id, seconds)
+    }
+
+    /// End a retainer at its current period end (User or Expert)
+    pub fn cancel_retainer(env: Env, caller: Address, retainer_id: u64) -> Result<(), MarketError> {
+        contract::cancel_retainer(&env, &caller, retainer_id)
+    }
+
+    /// Open an empty budget pool whose beneficiary limits reset every period seconds
+    pub fn create_pool(env: Env, owner: Address, period: u64) -> Result<u64, VaultError> {
+        contract::create_pool(&env, &owner, period)
+    }
+
+    /// Add funds to a budget pool (Owner-only)
+    pub fn deposit_to_pool(
+        env: Env,
+        owner: Address,
+        pool_id: u64,
+        amount: i128,
+    ) -> Result<(), VaultError> {
+        contract::deposit_to_pool(&env, &owner, pool_id, amount)
+    }
+
+    /// Withdraw unspent funds from a budget pool (Owner-only)
+    pub fn withdraw_from_pool(
+        env: Env,
+        owner: Address,
+        pool_id: u64,
+        amount: i128,
+    ) -> Result<(), VaultError> {
+        contract::withdraw_from_pool(&env, &owner, pool_id, amount)
+    }
+
+    /// Set a beneficiary's spending limit per period; zero removes them (Owner-only)
+    pub fn set_pool_beneficiary(
+        env: Env,
+        owner: Address,
+        pool_id: u64,
+        beneficiary: Address,
+        limit_per_period: i128,
+    ) -> Result<(), VaultError> {
+        contract::set_pool_beneficiary(&env, &owner, pool_id, &beneficiary, limit_per_period)
+    }
+
+    /// Add or remove an expert from a pool's allowlist (Owner-only)
+    pub fn set_pool_expert(
+        env: Env,
+        owner: Address,
+        pool_id: u64,
+        expert: Address,
+        allowed: bool,
+    ) -> Result<(), VaultError> {
+        contract::set_pool_expert(&env, &owner, pool_id, &expert, allowed)
+    }
+
+    /// Book a session paid from a budget pool (Beneficiary-only)
+    /// Refunds of the deposit return to the pool
+    #[allow(clippy::too_many_arguments)]
+    pub fn book_session_from_pool(
+        env: Env,
+        beneficiary: Address,
+        pool_id: u64,
+        expert: Address,
+        rate_per_second: i128,
+        max_duration: u64,
+        scheduled_start: u64,
+        grace_period: u64,
+    ) -> Result<u64, VaultError> {
+        contract::book_session_from_pool(
+            &env,
+            &beneficiary,
+            pool_id,
+            &expert,
+            rate_per_second,
+            max_duration,
+            scheduled_start,
+            grace_period,
+        )
+    }
+
+    /// Open a voucher campaign funded from the fee pool or the admin's tokens (Admin-only)
+    pub fn create_voucher_campaign(
+        env: Env,
+        discount: VoucherDiscount,
+        budget: i128,
+        per_user_limit: u32,
+        expires_at: u64,
+        from_fee_pool: bool,
+    ) -> Result<u64, VaultError> {
+        contract::create_voucher_campaign(
+            &env,
+            discount,
+            budget,
+            per_user_limit,
+            expires_at,
+            from_fee_pool,
+        )
+    }
+
+    /// Close a voucher campaign and return its remaining budget to the fee pool (Admin-only)
+    pub fn close_voucher_campaign(env: Env, campaign_id: u64) -> Result<(), VaultError> {
+        contract::close_voucher_campaign(&env, campaign_id)
+    }
+
     /// Reject a pending session (Expert-only)
     /// Experts can reject a pending booking, instantly refunding the user
+    /// The reason code and optional hash of an off-chain message are stored on the booking
     pub fn reject_session(
         env: Env,
         expert: Address,
         booking_id: u64,
+        reason: RejectionReason,
+        message_hash: Option<BytesN<32>>,
     ) -> Result<(), VaultError> {
-        contract::reject_session(&env, &expert, booking_id)
+        contract::reject_session(&env, &expert, booking_id, reason, message_hash)
     }
 
-    /// Get all booking IDs for a specific user
+    /// Propose a new flat rate, duration and start for a pending booking (Expert-only)
+    /// A later proposal replaces an earlier one until the user accepts
+    pub fn counter_propose(
+        env: Env,
+        expert: Address,
+        booking_id: u64,
+        new_rate: i128,
+        new_duration: u64,
+        new_start: u64,
+    ) -> Result<(), VaultError> {
+        contract::counter_propose(&env, &expert, booking_id, new_rate, new_duration, new_start)
+    }
+
+    /// Accept the expert's counter-proposal (User-only)
+    /// The user pays the increase in deposit or is refunded the decrease
+    pub fn accept_counter_proposal(
+        env: Env,
+        user: Address,
+        booking_id: u64,
+    ) -> Result<(), VaultError> {
+        contract::accept_counter_proposal(&env, &user, booking_id)
+    }
+
+    /// Propose moving a pending booking to a new start time (User or Expert)
+    /// A later proposal from either party replaces an earlier one
+    pub fn propose_reschedule(
+        env: Env,
+        caller: Address,
+        booking_id: u64,
+        new_start: u64,
+    ) -> Result<(), VaultError> {
+        contract::propose_reschedule(&env, &caller, booking_id, new_start)
+    }
+
+    /// Accept the other party's reschedule proposal (User or Expert)
+    /// The booking's start and finalization window move in place, keeping its deposit
+    pub fn accept_reschedule(env: Env, caller: Address, booking_id: u64) -> Result<(), VaultError> {
+        contract::accept_reschedule(&env, &caller, booking_id)
+    }
+
+    /// Create a fixed-price engagement paid in milestones
+    /// User deposits the sum of all milestone amounts upfront
+    pub fn create_engagement(
+        env: Env,
+        user: Address,
+        expert: Address,
+        milestones: Vec<i128>,
+        approval_timeout: u64,
+    ) -> Result<u64, MarketError> {
+        contract::create_engagement(&env, &user, &expert, milestones, approval_timeout)
+    }
+
+    /// Submit the current milestone for approval (Expert-only)
+    pub fn submit_milestone(
+        env: Env,
+        expert: Address,
+        engagement_id: u64,
+    ) -> Result<(), MarketError> {
+        contract::submit_milestone(&env, &expert, engagement_id)
+    }
+
+    /// Approve the submitted milestone and release its payment (User-only)
+    pub fn approve_milestone(
+        env: Env,
+        user: Address,
+        engagement_id: u64,
+    ) -> Result<(), MarketError> {
+        contract::approve_milestone(&env, &user, engagement_id)
+    }
+
+    /// Release a submitted milestone once the approval timeout has passed (permissionless)
+    pub fn auto_approve_milestone(env: Env, engagement_id: u64) -> Result<(), MarketError> {
+        contract::auto_approve_milestone(&env, engagement_id)
+    }
+
+    /// Cancel an engagement and refund unreleased milestones (User-only)
+    /// Not allowed while a milestone is awaiting approval
+    pub fn cancel_engagement(
+        env: Env,
+        user: Address,
+        engagement_id: u64,
+    ) -> Result<(), MarketError> {
+        contract::cancel_engagement(&env, &user, engagement_id)
+    }
+
+    /// Ask an expert a question for a fixed fee, answerable until the deadline
+    /// User deposits the fee upfront along with a hash of the question
+    pub fn ask_question(
+        env: Env,
+        user: Address,
+        expert: Address,
+        fee: i128,
+        question_hash: BytesN<32>,
+        deadline: u64,
+    ) -> Result<u64, MarketError> {
+        contract::ask_question(&env, &user, &expert, fee, question_hash, deadline)
+    }
+
+    /// Submit an answer hash before the deadline and collect the fee (Expert-only)
+    pub fn answer_question(
+        env: Env,
+        expert: Address,
+        question_id: u64,
+        answer_hash: BytesN<32>,
+    ) -> Result<(), MarketError> {
+        contract::answer_question(&env, &expert, question_id, answer_hash)
+    }
+
+    /// Refund an unanswered question once its deadline has passed (permissionless)
+    /// Pays the caller the keeper bounty from the fee pool
+    pub fn expire_question(
+        env: Env,
+        keeper: Address,
+        question_id: u64,
+    ) -> Result<(), MarketError> {
+        contract::expire_question(&env, &keeper, question_id)
+    }
+
+    /// Set the identity registry used to verify bounty answerers (Admin-only)
+    pub fn set_registry(env: Env, registry: Address) -> Result<(), VaultError> {
+        contract::set_registry(&env, &registry)
+    }
+
+    /// Post an open bounty that any verified expert can answer until expires_at
+    /// Poster deposits the bounty amount upfront
+    pub fn post_bounty(
+        env: Env,
+        poster: Address,
+        amount: i128,
+        question_hash: BytesN<32>,
+        expires_at: u64,
+    ) -> Result<u64, MarketError> {
+        contract::post_bounty(&env, &poster, amount, question_hash, expires_at)
+    }
+
+    /// Submit an answer hash to an open bounty (verified Expert-only)
+    /// Returns the submission index
+    pub fn submit_bounty_answer(
+        env: Env,
+        expert: Address,
+        bounty_id: u64,
+        content_hash: BytesN<32>,
+    ) -> Result<u32, MarketError> {
+        contract::submit_bounty_answer(&env, &expert, bounty_id, content_hash)
+    }
+
+    /// Split the bounty between one or more winning submissions (Poster-only)
+    /// Awards must add up to the full bounty amount and close 7 days after expiry
+    pub fn award_bounty(
+        env: Env,
+        poster: Address,
+        bounty_id: u64,
+        awards: Vec<BountyAward>,
+    ) -> Result<(), MarketError> {
+        contract::award_bounty(&env, &poster, bounty_id, awards)
+    }
+
+    /// Reclaim an expired bounty that received no answers (Poster-only)
+    pub fn reclaim_bounty(env: Env, poster: Address, bounty_id: u64) -> Result<(), MarketError> {
+        contract::reclaim_bounty(&env, &poster, bounty_id)
+    }
+
+    /// Settle a bounty the poster did not award within 7 days of expiry (permissionless)
+    /// The bounty is split equally between all answers, or refunded if there were none
+    pub fn settle_bounty(env: Env, bounty_id: u64) -> Result<(), MarketError> {
+        contract::settle_bounty(&env, bounty_id)
+    }
+
+    /// Auction a time slot to the highest bidder (Expert-only)
+    /// Bidding closes at end_time, which must be no later than scheduled_start
+    pub fn create_auction(
+        env: Env,
+        expert: Address,
+        reserve_price: i128,
+        max_duration: u64,
+        scheduled_start: u64,
+        grace_period: u64,
+        end_time: u64,
+    ) -> Result<u64, MarketError> {
+        contract::create_auction(
+            &env,
+            &expert,
+            reserve_price,
+            max_duration,
+            scheduled_start,
+            grace_period,
+            end_time,
+        )
+    }
+
+    /// Place a bid on an open auction
+    /// The bid is escrowed and the previous highest bidder is refunded immediately
+    pub fn place_bid(
+        env: Env,
+        bidder: Address,
+        auction_id: u64,
+        amount: i128,
+    ) -> Result<(), MarketError> {
+        contract::place_bid(&env, &bidder, auction_id, amount)
+    }
+
+    /// Settle an auction after bidding closes (permissionless)
+    /// Returns the funded booking created for the winner, if there was a bid
+    pub fn settle_auction(env: Env, auction_id: u64) -> Result<Option<u64>, MarketError> {
+        contract::settle_auction(&env, auction_id)
+    }
+
+    /// Get all booking, engagement and question IDs for a specific user
     pub fn get_user_bookings(env: Env, user: Address) -> Vec<u64> {
         storage::get_user_bookings(&env, &user)
     }
 
-    /// Get all booking IDs for a specific expert
+    /// Get all booking, engagement and question IDs for a specific expert
     pub fn get_expert_bookings(env: Env, expert: Address) -> Vec<u64> {
         storage::get_expert_bookings(&env, &expert)
     }
@@ -83,4 +650,143 @@ impl PaymentVaultContract {
     pub fn get_booking(env: Env, booking_id: u64) -> Option<BookingRecord> {
         storage::get_booking(&env, booking_id)
     }
+
+    /// Get engagement details by engagement ID (read-only)
+    pub fn get_engagement(env: Env, engagement_id: u64) -> Option<EngagementRecord> {
+        storage::get_engagement(&env, engagement_id)
+    }
+
+    /// Get question details by question ID (read-only)
+    pub fn get_question(env: Env, question_id: u64) -> Option<QuestionRecord> {
+        storage::get_question(&env, question_id)
+    }
+
+    /// Get bounty details by bounty ID (read-only)
+    pub fn get_bounty(env: Env, bounty_id: u64) -> Option<BountyRecord> {
+        storage::get_bounty(&env, bounty_id)
+    }
+
+    /// Get a page of bounty submissions starting at index start (at most 20 per page)
+    pub fn get_bounty_submissions(
+        env: Env,
+        bounty_id: u64,
+        start: u32,
+        limit: u32,
+    ) -> Vec<BountySubmission> {
+        contract::get_bounty_submissions(&env, bounty_id, start, limit)
+    }
+
+    /// Get auction details by auction ID (read-only)
+    pub fn get_auction(env: Env, auction_id: u64) -> Option<AuctionRecord> {
+        storage::get_auction(&env, auction_id)
+    }
+
+    /// Get retainer details by retainer ID (read-only)
+    pub fn get_retainer(env: Env, retainer_id: u64) -> Option<RetainerRecord> {
+        storage::get_retainer(&env, retainer_id)
+    }
+
+    /// Get budget pool details by pool ID (read-only)
+    pub fn get_pool(env: Env, pool_id: u64) -> Option<BudgetPool> {
+        storage::get_pool(&env, pool_id)
+    }
+
+    /// Get a beneficiary's limit and spend in a budget pool (read-only)
+    pub fn get_pool_beneficiary(
+        env: Env,
+        pool_id: u64,
+        beneficiary: Address,
+    ) -> Option<PoolBeneficiary> {
+        storage::get_pool_beneficiary(&env, pool_id, &beneficiary)
+    }
+
+    /// Get an expert's completed sessions, billed seconds, gross earnings, rejections and
+    /// reclaims (read-only)
+    pub fn get_expert_stats(env: Env, expert: Address) -> ExpertStats {
+        storage::get_expert_stats(&env, &expert)
+    }
+
+    /// Get a user's completed sessions and total spent per token (read-only)
+    pub fn get_user_stats(env: Env, user: Address) -> UserStats {
+        storage::get_user_stats(&env, &user)
+    }
+
+    /// Check whether an expert is accepting new bookings (read-only)
+    pub fn is_accepting_bookings(env: Env, expert: Address) -> bool {
+        storage::is_accepting_bookings(&env, &expert)
+    }
+
+    /// Check whether a user is on an expert's blocklist (read-only)
+    pub fn is_user_blocked(env: Env, expert: Address, user: Address) -> bool {
+        storage::is_user_blocked(&env, &expert, &user)
+    }
+
+    /// Compare the vault's recorded liabilities in a token against its balance (read-only)
+    pub fn get_solvency(env: Env, token: Address) -> SolvencyReport {
+        contract::get_solvency(&env, &token)
+    }
+
+    /// Get voucher campaign details by campaign ID (read-only)
+    pub fn get_voucher_campaign(env: Env, campaign_id: u64) -> Option<VoucherCampaign> {
+        storage::get_voucher_campaign(&env, campaign_id)
+    }
+
+    /// Get the billing policy an expert currently applies to new bookings
+    pub fn get_billing_policy(env: Env, expert: Address) -> BillingPolicy {
+        storage::get_billing_policy(&env, &expert)
+    }
+
+    /// Preview the expert payout and user refund for a booking and duration (read-only)
+    pub fn quote_settlement(
+        env: Env,
+        booking_id: u64,
+        actual_duration: u64,
+    ) -> Result<SettlementQuote, VaultError> {
+        contract::quote_settlement(&env, booking_id, actual_duration)
+    }
+
+    /// Get the counter-proposal awaiting a user's acceptance, if any (read-only)
+    pub fn get_counter_proposal(env: Env, booking_id: u64) -> Option<CounterProposal> {
+        storage::get_counter_proposal(&env, booking_id)
+    }
+
+    /// Get the reschedule proposal awaiting acceptance, if any (read-only)
+    pub fn get_reschedule_proposal(env: Env, booking_id: u64) -> Option<RescheduleProposal> {
+        storage::get_reschedule_proposal(&env, booking_id)
+    }
+
+    /// Get the accepted reschedules of a booking, oldest first (read-only)
+    pub fn get_reschedule_history(env: Env, booking_id: u64) -> Vec<RescheduleRecord> {
+        storage::get_reschedule_history(&env, booking_id)
+    }
+
+    /// Get the anti-spam limits applied to new bookings
+    pub fn get_booking_limits(env: Env) -> BookingLimits {
+        storage::get_booking_limits(&env)
+    }
+
+    /// Get how many open bookings a user holds across all experts (read-only)
+    pub fn get_open_bookings(env: Env, user: Address) -> u32 {
+        storage::get_open_bookings(&env, &user)
+    }
+
+    /// Get how many open bookings a user holds with one expert (read-only)
+    pub fn get_open_pair_bookings(env: Env, user: Address, expert: Address) -> u32 {
+        storage::get_open_pair_bookings(&env, &user, &expert)
+    }
+
+    /// Get the platform fees accrued in the vault
+    pub fn get_fee_pool(env: Env) -> i128 {
+        storage::get_fee_pool(&env)
+    }
+
+    /// Get the oracle quorum configuration, if any
+    pub fn get_oracle_quorum(env: Env) -> Option<OracleQuorum> {
+        storage::get_oracle_quorum(&env)
+    }
+
+    /// Get the duration reports submitted for a booking
+    pub fn get_duration_reports(env: Env, booking_id: u64) -> Vec<DurationReport> {
+        storage::get_duration_reports(&env, booking_id)
+    }
 }
@@ -0,0 +1,9 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Subset of the identity registry interface the vault depends on
+#[allow(dead_code)]
+#[contractclient(name = "IdentityRegistryClient")]
+pub trait IdentityRegistry {
+    /// Returns true only if the expert's status is Verified
+    fn is_verified(env: Env, expert: Address) -> bool;
+}
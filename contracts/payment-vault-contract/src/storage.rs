@@ -1,5 +1,10 @@
-use soroban_sdk::{contracttype, Address, Env};
-use crate::types::{BookingRecord, BookingStatus};
+use soroban_sdk::{contracttype, Address, BytesN, Env, Map};
+use crate::types::{
+    AuctionRecord, BillingPolicy, BookingLimits, BookingRecord, BookingStatus, BountyRecord,
+    BountySubmission, BudgetPool, CounterProposal, DurationReport, EngagementRecord, ExpertStats,
+    OracleQuorum, PoolBeneficiary, QuestionRecord, RescheduleProposal, RescheduleRecord,
+    RetainerRecord, RoundingMode, UserStats, VoucherCampaign,
+};
 
 #[contracttype]
 #[derive(Clone)]
@@ -11,6 +16,44 @@ pub enum DataKey {
     BookingCounter, // Counter for generating unique booking IDs
     UserBookings(Address), // User Address -> Vec<u64> of booking IDs
     ExpertBookings(Address), // Expert Address -> Vec<u64> of booking IDs
+    OracleQuorum, // M-of-N oracle set for high-value bookings
+    DurationReports(u64), // Booking ID -> Vec<DurationReport>
+    BillingPolicy(Address), // Expert Address -> BillingPolicy
+    PlatformFeeBps, // Platform fee taken from expert payouts, in basis points
+    FeePool, // Accrued platform fees held by the vault
+    KeeperBounty, // Paid from the fee pool to callers of expire_booking
+    Engagement(u64), // Engagement ID -> EngagementRecord
+    Question(u64), // Question ID -> QuestionRecord
+    Registry, // Identity registry contract used to verify experts
+    BountyCounter, // Counter for generating unique bounty IDs
+    Bounty(u64), // Bounty ID -> BountyRecord
+    BountySubmission(u64, u32), // (Bounty ID, index) -> BountySubmission
+    BountySubmitted(u64, Address), // (Bounty ID, Expert) -> bool
+    AuctionCounter, // Counter for generating unique auction IDs
+    Auction(u64), // Auction ID -> AuctionRecord
+    SigningKey(Address), // Expert Address -> ed25519 public key for quotes
+    QuoteNonce(Address, u64), // (Expert, nonce) -> bool once redeemed
+    VoucherCounter, // Counter for generating unique voucher campaign IDs
+    VoucherCampaign(u64), // Campaign ID -> VoucherCampaign
+    VoucherRedemptions(u64, Address), // (Campaign ID, User) -> u32 redemptions
+    RetainerCounter, // Counter for generating unique retainer IDs
+    Retainer(u64), // Retainer ID -> RetainerRecord
+    PoolCounter, // Counter for generating unique budget pool IDs
+    Pool(u64), // Pool ID -> BudgetPool
+    PoolBeneficiary(u64, Address), // (Pool ID, Beneficiary) -> PoolBeneficiary
+    PoolExpert(u64, Address), // (Pool ID, Expert) -> bool when allowlisted
+    LockedTotal(Address), // Token -> escrowed deposits, retainers, voucher budgets and pools
+    EarningsTotal(Address), // Token -> expert pay accrued but not yet withdrawn
+    ExpertStats(Address), // Expert Address -> ExpertStats
+    UserStats(Address), // User Address -> UserStats
+    BookingsPaused(Address), // Expert Address -> true while not accepting bookings
+    BlockedUser(Address, Address), // (Expert, User) -> true while blocked
+    BookingLimits, // Anti-spam limits applied to new bookings
+    OpenBookings(Address), // User Address -> count of open bookings
+    OpenPairBookings(Address, Address), // (User, Expert) -> count of open bookings
+    CounterProposal(u64), // Booking ID -> CounterProposal awaiting the user
+    RescheduleProposal(u64), // Booking ID -> RescheduleProposal awaiting the other party
+    RescheduleHistory(u64), // Booking ID -> Vec<RescheduleRecord>
 }
 
 // --- Admin ---
@@ -22,7 +65,6 @@ pub fn set_admin(env: &Env, admin: &Address) {
     env.storage().instance().set(&DataKey::Admin, admin);
 }
 
-#[allow(dead_code)]
 pub fn get_admin(env: &Env) -> Option<Address> {
     env.storage().instance().get(&DataKey::Admin)
 }
@@ -45,6 +87,111 @@ pub fn get_oracle(env: &Env) -> Address {
     env.storage().instance().get(&DataKey::Oracle).unwrap()
 }
 
+// --- Oracle Quorum ---
+pub fn set_oracle_quorum(env: &Env, quorum: &OracleQuorum) {
+    env.storage().instance().set(&DataKey::OracleQuorum, quorum);
+}
+
+pub fn get_oracle_quorum(env: &Env) -> Option<OracleQuorum> {
+    env.storage().instance().get(&DataKey::OracleQuorum)
+}
+
+// --- Platform Fees ---
+pub fn set_platform_fee_bps(env: &Env, fee_bps: u32) {
+    env.storage().instance().set(&DataKey::PlatformFeeBps, &fee_bps);
+}
+
+pub fn get_platform_fee_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&DataKey::PlatformFeeBps)
+        .unwrap_or(0)
+}
+
+pub fn set_fee_pool(env: &Env, amount: i128) {
+    env.storage().instance().set(&DataKey::FeePool, &amount);
+}
+
+pub fn get_fee_pool(env: &Env) -> i128 {
+    env.storage().instance().get(&DataKey::FeePool).unwrap_or(0)
+}
+
+pub fn set_keeper_bounty(env: &Env, amount: i128) {
+    env.storage().instance().set(&DataKey::KeeperBounty, &amount);
+}
+
+pub fn get_keeper_bounty(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::KeeperBounty)
+        .unwrap_or(0)
+}
+
+// --- Booking Limits ---
+pub fn set_booking_limits(env: &Env, limits: &BookingLimits) {
+    env.storage().instance().set(&DataKey::BookingLimits, limits);
+}
+
+pub fn get_booking_limits(env: &Env) -> BookingLimits {
+    env.storage()
+        .instance()
+        .get(&DataKey::BookingLimits)
+        .unwrap_or(BookingLimits {
+            max_pending_per_user: 0,
+            max_pending_per_pair: 0,
+            min_deposit: 0,
+        })
+}
+
+pub fn get_open_bookings(env: &Env, user: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OpenBookings(user.clone()))
+        .unwrap_or(0)
+}
+
+pub fn get_open_pair_bookings(env: &Env, user: &Address, expert: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::OpenPairBookings(user.clone(), expert.clone()))
+        .unwrap_or(0)
+}
+
+/// Counts a new open booking for the user, both overall and with the expert
+pub fn increment_open_bookings(env: &Env, user: &Address, expert: &Address) {
+    let open = get_open_bookings(env, user) + 1;
+    env.storage()
+        .persistent()
+        .set(&DataKey::OpenBookings(user.clone()), &open);
+
+    let open_pair = get_open_pair_bookings(env, user, expert) + 1;
+    env.storage()
+        .persistent()
+        .set(&DataKey::OpenPairBookings(user.clone(), expert.clone()), &open_pair);
+}
+
+/// Releases an open booking once it reaches a final status
+pub fn decrement_open_bookings(env: &Env, user: &Address, expert: &Address) {
+    let open = get_open_bookings(env, user).saturating_sub(1);
+    env.storage()
+        .persistent()
+        .set(&DataKey::OpenBookings(user.clone()), &open);
+
+    let open_pair = get_open_pair_bookings(env, user, expert).saturating_sub(1);
+    env.storage()
+        .persistent()
+        .set(&DataKey::OpenPairBookings(user.clone(), expert.clone()), &open_pair);
+}
+
+// --- Identity Registry ---
+pub fn set_registry(env: &Env, registry: &Address) {
+    env.storage().instance().set(&DataKey::Registry, registry);
+}
+
+pub fn get_registry(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&DataKey::Registry)
+}
+
 // --- Booking Counter ---
 pub fn get_next_booking_id(env: &Env) -> u64 {
     let current: u64 = env
@@ -77,6 +224,88 @@ pub fn update_booking_status(env: &Env, booking_id: u64, status: BookingStatus)
     }
 }
 
+// --- Counter Proposals ---
+pub fn save_counter_proposal(env: &Env, booking_id: u64, proposal: &CounterProposal) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::CounterProposal(booking_id), proposal);
+}
+
+pub fn get_counter_proposal(env: &Env, booking_id: u64) -> Option<CounterProposal> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::CounterProposal(booking_id))
+}
+
+pub fn remove_counter_proposal(env: &Env, booking_id: u64) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::CounterProposal(booking_id));
+}
+
+// --- Reschedules ---
+pub fn save_reschedule_proposal(env: &Env, booking_id: u64, proposal: &RescheduleProposal) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::RescheduleProposal(booking_id), proposal);
+}
+
+pub fn get_reschedule_proposal(env: &Env, booking_id: u64) -> Option<RescheduleProposal> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RescheduleProposal(booking_id))
+}
+
+pub fn remove_reschedule_proposal(env: &Env, booking_id: u64) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::RescheduleProposal(booking_id));
+}
+
+pub fn get_reschedule_history(
+    env: &Env,
+    booking_id: u64,
+) -> soroban_sdk::Vec<RescheduleRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RescheduleHistory(booking_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn add_reschedule_record(env: &Env, booking_id: u64, record: RescheduleRecord) {
+    let mut history = get_reschedule_history(env, booking_id);
+    history.push_back(record);
+    env.storage()
+        .persistent()
+        .set(&DataKey::RescheduleHistory(booking_id), &history);
+}
+
+// --- Engagements ---
+pub fn save_engagement(env: &Env, engagement: &EngagementRecord) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Engagement(engagement.id), engagement);
+}
+
+pub fn get_engagement(env: &Env, engagement_id: u64) -> Option<EngagementRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Engagement(engagement_id))
+}
+
+// --- Questions ---
+pub fn save_question(env: &Env, question: &QuestionRecord) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Question(question.id), question);
+}
+
+pub fn get_question(env: &Env, question_id: u64) -> Option<QuestionRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Question(question_id))
+}
+
 // --- User and Expert Booking Lists ---
 pub fn add_booking_to_user_list(env: &Env, user: &Address, booking_id: u64) {
     let mut user_bookings: soroban_sdk::Vec<u64> = env
@@ -119,3 +348,366 @@ pub fn get_expert_bookings(env: &Env, expert: &Address) -> soroban_sdk::Vec<u64>
         .get(&DataKey::ExpertBookings(expert.clone()))
         .unwrap_or(soroban_sdk::Vec::new(env))
 }
+
+// --- Duration Reports ---
+pub fn get_duration_reports(env: &Env, booking_id: u64) -> soroban_sdk::Vec<DurationReport> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::DurationReports(booking_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn save_duration_reports(
+    env: &Env,
+    booking_id: u64,
+    reports: &soroban_sdk::Vec<DurationReport>,
+) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::DurationReports(booking_id), reports);
+}
+
+pub fn remove_duration_reports(env: &Env, booking_id: u64) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::DurationReports(booking_id));
+}
+
+// --- Billing Policies ---
+pub fn set_billing_policy(env: &Env, expert: &Address, policy: &BillingPolicy) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::BillingPolicy(expert.clone()), policy);
+}
+
+/// Experts without a policy are billed per second with no minimum
+pub fn get_billing_policy(env: &Env, expert: &Address) -> BillingPolicy {
+    env.storage()
+        .persistent()
+        .get(&DataKey::BillingPolicy(expert.clone()))
+        .unwrap_or(BillingPolicy {
+            increment: 1,
+            min_duration: 0,
+            rounding: RoundingMode::Down,
+        })
+}
+
+// --- Bounties ---
+pub fn get_next_bounty_id(env: &Env) -> u64 {
+    let current: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::BountyCounter)
+        .unwrap_or(0);
+    let next = current + 1;
+    env.storage().instance().set(&DataKey::BountyCounter, &next);
+    next
+}
+
+pub fn save_bounty(env: &Env, bounty: &BountyRecord) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Bounty(bounty.id), bounty);
+}
+
+pub fn get_bounty(env: &Env, bounty_id: u64) -> Option<BountyRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Bounty(bounty_id))
+}
+
+pub fn save_bounty_submission(
+    env: &Env,
+    bounty_id: u64,
+    index: u32,
+    submission: &BountySubmission,
+) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::BountySubmission(bounty_id, index), submission);
+    env.storage()
+        .persistent()
+        .set(&DataKey::BountySubmitted(bounty_id, submission.expert.clone()), &true);
+}
+
+pub fn get_bounty_submission(env: &Env, bounty_id: u64, index: u32) -> Option<BountySubmission> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::BountySubmission(bounty_id, index))
+}
+
+pub fn has_bounty_submission(env: &Env, bounty_id: u64, expert: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::BountySubmitted(bounty_id, expert.clone()))
+}
+
+// --- Auctions ---
+pub fn get_next_auction_id(env: &Env) -> u64 {
+    let current: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::AuctionCounter)
+        .unwrap_or(0);
+    let next = current + 1;
+    env.storage().instance().set(&DataKey::AuctionCounter, &next);
+    next
+}
+
+pub fn save_auction(env: &Env, auction: &AuctionRecord) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Auction(auction.id), auction);
+}
+
+pub fn get_auction(env: &Env, auction_id: u64) -> Option<AuctionRecord> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Auction(auction_id))
+}
+
+// --- Signed Quotes ---
+pub fn set_signing_key(env: &Env, expert: &Address, public_key: &BytesN<32>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::SigningKey(expert.clone()), public_key);
+}
+
+pub fn get_signing_key(env: &Env, expert: &Address) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SigningKey(expert.clone()))
+}
+
+pub fn is_quote_nonce_used(env: &Env, expert: &Address, nonce: u64) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::QuoteNonce(expert.clone(), nonce))
+}
+
+pub fn mark_quote_nonce_used(env: &Env, expert: &Address, nonce: u64) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::QuoteNonce(expert.clone(), nonce), &true);
+}
+
+// --- Voucher Campaigns ---
+pub fn get_next_voucher_id(env: &Env) -> u64 {
+    let current: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::VoucherCounter)
+        .unwrap_or(0);
+    let next = current + 1;
+    env.storage().instance().set(&DataKey::VoucherCounter, &next);
+    next
+}
+
+pub fn save_voucher_campaign(env: &Env, campaign: &VoucherCampaign) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::VoucherCampaign(campaign.id), campaign);
+}
+
+pub fn get_voucher_campaign(env: &Env, campaign_id: u64) -> Option<VoucherCampaign> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::VoucherCampaign(campaign_id))
+}
+
+pub fn get_voucher_redemptions(env: &Env, campaign_id: u64, user: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::VoucherRedemptions(campaign_id, user.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_voucher_redemptions(env: &Env, campaign_id: u64, user: &Address, count: u32) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::VoucherRedemptions(campaign_id, user.clone()), &count);
+}
+
+// --- Retainers ---
+pub fn get_next_retainer_id(env: &Env) -> u64 {
+    let current: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::RetainerCounter)
+        .unwrap_or(0);
+    let next = current + 1;
+    env.storage().instance().set(&DataKey::RetainerCounter, &next);
+    next
+}
+
+pub fn save_retainer(env: &Env, retainer: &RetainerRecord) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Retainer(retainer.id), retainer);
+}
+
+pub fn get_retainer(env: &Env, retainer_id: u64) -> Option<RetainerRecord> {
+    env.storage().persistent().get(&DataKey::Retainer(retainer_id))
+}
+
+// --- Budget Pools ---
+pub fn get_next_pool_id(env: &Env) -> u64 {
+    let current: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::PoolCounter)
+        .unwrap_or(0);
+    let next = current + 1;
+    env.storage().instance().set(&DataKey::PoolCounter, &next);
+    next
+}
+
+pub fn save_pool(env: &Env, pool: &BudgetPool) {
+    env.storage().persistent().set(&DataKey::Pool(pool.id), pool);
+}
+
+pub fn get_pool(env: &Env, pool_id: u64) -> Option<BudgetPool> {
+    env.storage().persistent().get(&DataKey::Pool(pool_id))
+}
+
+pub fn set_pool_beneficiary(
+    env: &Env,
+    pool_id: u64,
+    beneficiary: &Address,
+    entry: &PoolBeneficiary,
+) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::PoolBeneficiary(pool_id, beneficiary.clone()), entry);
+}
+
+pub fn remove_pool_beneficiary(env: &Env, pool_id: u64, beneficiary: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::PoolBeneficiary(pool_id, beneficiary.clone()));
+}
+
+pub fn get_pool_beneficiary(
+    env: &Env,
+    pool_id: u64,
+    beneficiary: &Address,
+) -> Option<PoolBeneficiary> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PoolBeneficiary(pool_id, beneficiary.clone()))
+}
+
+pub fn set_pool_expert(env: &Env, pool_id: u64, expert: &Address, allowed: bool) {
+    let key = DataKey::PoolExpert(pool_id, expert.clone());
+    if allowed {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub fn is_pool_expert(env: &Env, pool_id: u64, expert: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::PoolExpert(pool_id, expert.clone()))
+}
+
+// --- Solvency ---
+pub fn get_locked_total(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::LockedTotal(token.clone()))
+        .unwrap_or(0)
+}
+
+/// Adds to (or, with a negative amount, releases from) the vault token's locked total
+pub fn adjust_locked(env: &Env, amount: i128) {
+    let token = get_token(env);
+    let total = get_locked_total(env, &token) + amount;
+    env.storage()
+        .instance()
+        .set(&DataKey::LockedTotal(token), &total);
+}
+
+pub fn get_earnings_total(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DataKey::EarningsTotal(token.clone()))
+        .unwrap_or(0)
+}
+
+/// Adds to (or, with a negative amount, pays out of) the vault token's earnings total
+pub fn adjust_earnings(env: &Env, amount: i128) {
+    let token = get_token(env);
+    let total = get_earnings_total(env, &token) + amount;
+    env.storage()
+        .instance()
+        .set(&DataKey::EarningsTotal(token), &total);
+}
+
+// --- Stats ---
+pub fn get_expert_stats(env: &Env, expert: &Address) -> ExpertStats {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ExpertStats(expert.clone()))
+        .unwrap_or(ExpertStats {
+            completed_sessions: 0,
+            seconds_billed: 0,
+            gross_earnings: Map::new(env),
+            rejections: 0,
+            reclaims: 0,
+        })
+}
+
+pub fn save_expert_stats(env: &Env, expert: &Address, stats: &ExpertStats) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::ExpertStats(expert.clone()), stats);
+}
+
+pub fn get_user_stats(env: &Env, user: &Address) -> UserStats {
+    env.storage()
+        .persistent()
+        .get(&DataKey::UserStats(user.clone()))
+        .unwrap_or(UserStats {
+            sessions: 0,
+            total_spent: Map::new(env),
+        })
+}
+
+pub fn save_user_stats(env: &Env, user: &Address, stats: &UserStats) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::UserStats(user.clone()), stats);
+}
+
+// --- Expert Availability ---
+pub fn set_accepting_bookings(env: &Env, expert: &Address, accepting: bool) {
+    let key = DataKey::BookingsPaused(expert.clone());
+    if accepting {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &true);
+    }
+}
+
+pub fn is_accepting_bookings(env: &Env, expert: &Address) -> bool {
+    !env.storage()
+        .persistent()
+        .has(&DataKey::BookingsPaused(expert.clone()))
+}
+
+pub fn set_user_blocked(env: &Env, expert: &Address, user: &Address, blocked: bool) {
+    let key = DataKey::BlockedUser(expert.clone(), user.clone());
+    if blocked {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub fn is_user_blocked(env: &Env, expert: &Address, user: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&DataKey::BlockedUser(expert.clone(), user.clone()))
+}
@@ -1,17 +1,37 @@
 #![cfg(test)]
+use crate::error::{MarketError, VaultError};
+use crate::events::{AuctionSettledEvent, SessionFinalizedEvent, EVENT_VERSION};
+use crate::types::{
+    BillingPolicy, BookingStatus, BountyAward, OptionalRejectionReason, PricingTier, Quote,
+    RejectionReason, RoundingMode, VoucherDiscount,
+};
 use crate::{PaymentVaultContract, PaymentVaultContractClient};
+use ed25519_dalek::{Signer, SigningKey};
+use identity_registry_contract::{IdentityRegistryContract, IdentityRegistryContractClient};
 use soroban_sdk::{
-    testutils::{Address as _, Ledger},
-    token, Address, Env,
+    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke},
+    token,
+    xdr::ToXdr,
+    Address, BytesN, Env, IntoVal, Symbol, TryIntoVal, Vec,
 };
 
 extern crate std;
 
+/// Grace period used by tests that don't exercise the booking window
+const GRACE_PERIOD: u64 = 3_600;
+
 fn create_client<'a>(env: &'a Env) -> PaymentVaultContractClient<'a> {
     let contract_id = env.register(PaymentVaultContract, ());
     PaymentVaultContractClient::new(env, &contract_id)
 }
 
+/// The vault holds exactly what it owes: escrow, fees and unwithdrawn earnings
+fn assert_solvent(client: &PaymentVaultContractClient, token: &Address) {
+    let report = client.get_solvency(token);
+    assert_eq!(report.balance, report.locked + report.fees + report.earnings);
+    assert_eq!(report.surplus, 0);
+}
+
 fn create_token_contract<'a>(env: &'a Env, admin: &Address) -> token::StellarAssetClient<'a> {
     let contract = env.register_stellar_asset_contract_v2(admin.clone());
     token::StellarAssetClient::new(env, &contract.address())
@@ -58,7 +78,15 @@ fn test_partial_duration_scenario() {
     // Total deposit = 10 * 100 = 1000 tokens
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Verify user's balance decreased
     assert_eq!(token.balance(&user), 9_000);
@@ -94,7 +122,15 @@ fn test_full_duration_no_refund() {
     // Book session
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Oracle finalizes with full duration (100 seconds)
     let actual_duration = 100_u64;
@@ -125,7 +161,15 @@ fn test_double_finalization_protection() {
 
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // First finalization succeeds
     let actual_duration = 50_u64;
@@ -156,7 +200,15 @@ fn test_oracle_authorization_enforcement() {
 
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Clear all mocked auths to test Oracle authorization
     env.set_auths(&[]);
@@ -192,7 +244,15 @@ fn test_zero_duration_finalization() {
 
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Oracle finalizes with 0 duration (session cancelled)
     let actual_duration = 0_u64;
@@ -255,7 +315,15 @@ fn test_book_session_balance_transfer() {
     assert_eq!(token.balance(&client.address), 0);
 
     // Book session
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Acceptance Criteria #1: User's balance decreases
     assert_eq!(token.balance(&user), initial_balance - expected_deposit);
@@ -268,7 +336,15 @@ fn test_book_session_balance_transfer() {
 
     // Create another booking to verify uniqueness
     token.mint(&user, &expected_deposit); // Mint more tokens for second booking
-    let booking_id_2 = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id_2 = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Second booking should have different ID
     assert_eq!(booking_id_2, 2);
@@ -296,8 +372,24 @@ fn test_get_user_and_expert_bookings() {
     // Create 2 bookings for the same user with different experts
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id_1 = client.book_session(&user, &expert1, &rate_per_second, &max_duration);
-    let booking_id_2 = client.book_session(&user, &expert2, &rate_per_second, &max_duration);
+    let booking_id_1 = client.book_session(
+        &user,
+        &expert1,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
+    let booking_id_2 = client.book_session(
+        &user,
+        &expert2,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Test get_user_bookings - should return 2 bookings
     let user_bookings = client.get_user_bookings(&user);
@@ -349,7 +441,15 @@ fn test_reclaim_stale_session_too_early() {
     // Create booking
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // User tries to reclaim immediately (should fail - too early)
     let result = client.try_reclaim_stale_session(&user, &booking_id);
@@ -380,7 +480,15 @@ fn test_reclaim_stale_session_success() {
     // Create booking
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Advance ledger timestamp by 25 hours (90000 seconds)
     env.ledger().set_timestamp(env.ledger().timestamp() + 90_000);
@@ -416,7 +524,15 @@ fn test_reclaim_stale_session_wrong_user() {
     // Create booking
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Advance ledger timestamp by 25 hours
     env.ledger().set_timestamp(env.ledger().timestamp() + 90_000);
@@ -449,7 +565,15 @@ fn test_reclaim_already_finalized() {
     // Create booking
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Oracle finalizes the session
     client.finalize_session(&booking_id, &50);
@@ -482,14 +606,23 @@ fn test_expert_rejects_pending_session() {
     // Create booking
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Verify initial state
     assert_eq!(token.balance(&user), 9_000);
     assert_eq!(token.balance(&client.address), 1_000);
 
     // Expert rejects the session
-    let result = client.try_reject_session(&expert, &booking_id);
+    let result =
+        client.try_reject_session(&expert, &booking_id, &RejectionReason::Unavailable, &None);
     assert!(result.is_ok());
 
     // Verify user balance increased (full refund)
@@ -499,7 +632,6 @@ fn test_expert_rejects_pending_session() {
 
     // Verify booking status is Rejected
     let booking = client.get_booking(&booking_id).unwrap();
-    use crate::types::BookingStatus;
     assert_eq!(booking.status, BookingStatus::Rejected);
 }
 
@@ -522,10 +654,19 @@ fn test_user_cannot_reject_session() {
 
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // User tries to reject their own session (should fail - not authorized)
-    let result = client.try_reject_session(&user, &booking_id);
+    let result =
+        client.try_reject_session(&user, &booking_id, &RejectionReason::Unavailable, &None);
     assert!(result.is_err());
 
     // Verify funds still in contract
@@ -551,13 +692,22 @@ fn test_reject_already_complete_session() {
 
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Oracle finalizes the session
     client.finalize_session(&booking_id, &50);
 
     // Expert tries to reject after completion (should fail - not pending)
-    let result = client.try_reject_session(&expert, &booking_id);
+    let result =
+        client.try_reject_session(&expert, &booking_id, &RejectionReason::Unavailable, &None);
     assert!(result.is_err());
 }
 
@@ -580,14 +730,23 @@ fn test_reject_already_reclaimed_session() {
 
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Advance time and user reclaims
     env.ledger().set_timestamp(env.ledger().timestamp() + 90_000);
     client.reclaim_stale_session(&user, &booking_id);
 
     // Expert tries to reject after reclamation (should fail - not pending)
-    let result = client.try_reject_session(&expert, &booking_id);
+    let result =
+        client.try_reject_session(&expert, &booking_id, &RejectionReason::Unavailable, &None);
     assert!(result.is_err());
 }
 
@@ -611,10 +770,19 @@ fn test_wrong_expert_cannot_reject() {
 
     let rate_per_second = 10_i128;
     let max_duration = 100_u64;
-    let booking_id = client.book_session(&user, &expert, &rate_per_second, &max_duration);
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &rate_per_second,
+        &max_duration,
+        &0,
+        &GRACE_PERIOD,
+        &None,
+    );
 
     // Different expert tries to reject (should fail - not authorized)
-    let result = client.try_reject_session(&wrong_expert, &booking_id);
+    let result =
+        client.try_reject_session(&wrong_expert, &booking_id, &RejectionReason::Other, &None);
     assert!(result.is_err());
 
     // Verify funds still in contract
@@ -635,7 +803,2914 @@ fn test_reject_nonexistent_booking() {
     client.init(&admin, &token, &oracle);
 
     // Expert tries to reject non-existent booking (should fail - not found)
-    let result = client.try_reject_session(&expert, &999);
+    let result = client.try_reject_session(&expert, &999, &RejectionReason::Unavailable, &None);
     assert!(result.is_err());
 }
 
+
+#[test]
+fn test_high_value_booking_requires_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let oracle_a = Address::generate(&env);
+    let oracle_b = Address::generate(&env);
+    let oracle_c = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // 2-of-3 quorum for deposits of 1000 tokens or more
+    let oracles = soroban_sdk::vec![&env, oracle_a.clone(), oracle_b.clone(), oracle_c.clone()];
+    client.set_oracle_quorum(&oracles, &2, &1_000);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    // The single oracle can no longer settle this booking
+    let result = client.try_finalize_session(&booking_id, &50);
+    assert_eq!(result, Err(Ok(VaultError::QuorumRequired)));
+
+    // First report does not move funds
+    client.submit_duration_report(&oracle_a, &booking_id, &50);
+    assert_eq!(token.balance(&client.address), 1_000);
+
+    // Same oracle cannot report twice
+    let result = client.try_submit_duration_report(&oracle_a, &booking_id, &50);
+    assert_eq!(result, Err(Ok(VaultError::DuplicateReport)));
+
+    // Second matching report settles the booking
+    client.submit_duration_report(&oracle_b, &booking_id, &50);
+    assert_eq!(token.balance(&expert), 500);
+    assert_eq!(token.balance(&user), 9_500);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Complete);
+    assert_eq!(client.get_duration_reports(&booking_id).len(), 2);
+}
+
+#[test]
+fn test_low_value_booking_uses_single_oracle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let oracle_a = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let oracles = soroban_sdk::vec![&env, oracle_a.clone()];
+    client.set_oracle_quorum(&oracles, &1, &5_000);
+
+    // Deposit of 1000 is below the quorum threshold
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    let result = client.try_submit_duration_report(&oracle_a, &booking_id, &50);
+    assert_eq!(result, Err(Ok(VaultError::QuorumNotRequired)));
+
+    client.finalize_session(&booking_id, &50);
+    assert_eq!(token.balance(&expert), 500);
+}
+
+#[test]
+fn test_conflicting_reports_dispute_booking() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let oracle_a = Address::generate(&env);
+    let oracle_b = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let oracles = soroban_sdk::vec![&env, oracle_a.clone(), oracle_b.clone()];
+    client.set_oracle_quorum(&oracles, &2, &1_000);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    // Oracles outside the set are rejected
+    let result = client.try_submit_duration_report(&outsider, &booking_id, &50);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+
+    client.submit_duration_report(&oracle_a, &booking_id, &50);
+    client.submit_duration_report(&oracle_b, &booking_id, &80);
+
+    // Funds stay locked while disputed
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Disputed);
+    assert_eq!(token.balance(&client.address), 1_000);
+
+    // Admin resolves the dispute
+    client.resolve_dispute(&booking_id, &60);
+    assert_eq!(token.balance(&expert), 600);
+    assert_eq!(token.balance(&user), 9_400);
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Complete);
+
+    // Cannot resolve twice
+    let result = client.try_resolve_dispute(&booking_id, &60);
+    assert_eq!(result, Err(Ok(VaultError::BookingNotDisputed)));
+}
+
+#[test]
+fn test_invalid_oracle_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let oracle_a = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    let client = create_client(&env);
+    client.init(&admin, &token, &oracle);
+
+    // Threshold larger than the set
+    let oracles = soroban_sdk::vec![&env, oracle_a.clone()];
+    let result = client.try_set_oracle_quorum(&oracles, &2, &1_000);
+    assert_eq!(result, Err(Ok(VaultError::InvalidQuorum)));
+
+    // Duplicate oracles do not count as distinct
+    let oracles = soroban_sdk::vec![&env, oracle_a.clone(), oracle_a.clone()];
+    let result = client.try_set_oracle_quorum(&oracles, &2, &1_000);
+    assert_eq!(result, Err(Ok(VaultError::InvalidQuorum)));
+}
+
+#[test]
+fn test_billing_policy_minimum_and_round_up() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &100_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // Per-minute billing rounded up, 15-minute minimum
+    let policy = BillingPolicy {
+        increment: 60,
+        min_duration: 900,
+        rounding: RoundingMode::Up,
+    };
+    client.set_billing_policy(&expert, &policy);
+
+    // rate = 1 token/second, max_duration = 1 hour
+    let booking_id = client.book_session(&user, &expert, &1, &3_600, &0, &GRACE_PERIOD, &None);
+    assert_eq!(client.get_booking(&booking_id).unwrap().billing, policy);
+
+    // 5 minutes is billed as the 15-minute minimum
+    let quote = client.quote_settlement(&booking_id, &300);
+    assert_eq!(quote.billable_duration, 900);
+
+    // 20m01s rounds up to 21 minutes
+    let quote = client.quote_settlement(&booking_id, &1_201);
+    assert_eq!(quote.billable_duration, 1_260);
+    assert_eq!(quote.expert_pay, 1_260);
+    assert_eq!(quote.refund, 3_600 - 1_260);
+
+    // Rounding never bills beyond the booked duration
+    let quote = client.quote_settlement(&booking_id, &3_590);
+    assert_eq!(quote.billable_duration, 3_600);
+
+    // No-show is not billed
+    let quote = client.quote_settlement(&booking_id, &0);
+    assert_eq!(quote.expert_pay, 0);
+
+    // Finalization pays what was quoted
+    client.finalize_session(&booking_id, &1_201);
+    assert_eq!(token.balance(&expert), 1_260);
+    assert_eq!(token.balance(&user), 100_000 - 1_260);
+}
+
+#[test]
+fn test_billing_policy_nearest_and_snapshot() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &100_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // Booked under the default per-second policy
+    let per_second_id = client.book_session(&user, &expert, &1, &3_600, &0, &GRACE_PERIOD, &None);
+
+    client.set_billing_policy(
+        &expert,
+        &BillingPolicy {
+            increment: 60,
+            min_duration: 0,
+            rounding: RoundingMode::Nearest,
+        },
+    );
+    let per_minute_id = client.book_session(&user, &expert, &1, &3_600, &0, &GRACE_PERIOD, &None);
+
+    // Policy changes do not affect existing bookings
+    assert_eq!(client.quote_settlement(&per_second_id, &89).billable_duration, 89);
+
+    assert_eq!(client.quote_settlement(&per_minute_id, &89).billable_duration, 60);
+    assert_eq!(client.quote_settlement(&per_minute_id, &90).billable_duration, 120);
+}
+
+#[test]
+fn test_invalid_billing_policy_and_quote() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let result = client.try_set_billing_policy(
+        &expert,
+        &BillingPolicy {
+            increment: 0,
+            min_duration: 0,
+            rounding: RoundingMode::Down,
+        },
+    );
+    assert_eq!(result, Err(Ok(VaultError::InvalidBillingPolicy)));
+
+    // Increments and minimum charges are capped at one day
+    let result = client.try_set_billing_policy(
+        &expert,
+        &BillingPolicy {
+            increment: 60,
+            min_duration: 86_401,
+            rounding: RoundingMode::Up,
+        },
+    );
+    assert_eq!(result, Err(Ok(VaultError::InvalidBillingPolicy)));
+
+    let result = client.try_set_billing_policy(
+        &expert,
+        &BillingPolicy {
+            increment: u64::MAX,
+            min_duration: 0,
+            rounding: RoundingMode::Nearest,
+        },
+    );
+    assert_eq!(result, Err(Ok(VaultError::InvalidBillingPolicy)));
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    // Duration beyond the booked maximum cannot be quoted or settled
+    let result = client.try_quote_settlement(&booking_id, &101);
+    assert_eq!(result, Err(Ok(VaultError::InvalidAmount)));
+
+    let result = client.try_quote_settlement(&999, &50);
+    assert_eq!(result, Err(Ok(VaultError::BookingNotFound)));
+}
+
+#[test]
+fn test_tiered_pricing_deposit_and_boundaries() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &100_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // First 30 minutes at 2 tokens/second, then 1 token/second
+    let pricing = soroban_sdk::vec![
+        &env,
+        PricingTier { threshold: 0, rate_per_second: 2 },
+        PricingTier { threshold: 1_800, rate_per_second: 1 },
+    ];
+    let booking_id =
+        client.book_session_tiered(&user, &expert, &pricing, &3_600, &0, &GRACE_PERIOD);
+
+    // Deposit = 1800 * 2 + 1800 * 1
+    assert_eq!(token.balance(&client.address), 5_400);
+    let booking = client.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.total_deposit, 5_400);
+    assert_eq!(booking.rate_per_second, 2);
+
+    // Boundaries of the first tier
+    assert_eq!(client.quote_settlement(&booking_id, &1_799).expert_pay, 3_598);
+    assert_eq!(client.quote_settlement(&booking_id, &1_800).expert_pay, 3_600);
+    assert_eq!(client.quote_settlement(&booking_id, &1_801).expert_pay, 3_601);
+    assert_eq!(client.quote_settlement(&booking_id, &3_600).expert_pay, 5_400);
+
+    client.finalize_session(&booking_id, &2_400);
+    assert_eq!(token.balance(&expert), 4_200);
+    assert_eq!(token.balance(&user), 100_000 - 4_200);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_tiered_pricing_long_session_discount() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &100_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // Three tiers; the booked duration ends before the last one
+    let pricing = soroban_sdk::vec![
+        &env,
+        PricingTier { threshold: 0, rate_per_second: 10 },
+        PricingTier { threshold: 100, rate_per_second: 5 },
+        PricingTier { threshold: 1_000, rate_per_second: 1 },
+    ];
+    let booking_id = client.book_session_tiered(&user, &expert, &pricing, &500, &0, &GRACE_PERIOD);
+
+    // 100 * 10 + 400 * 5
+    assert_eq!(client.get_booking(&booking_id).unwrap().total_deposit, 3_000);
+    assert_eq!(client.quote_settlement(&booking_id, &100).expert_pay, 1_000);
+    assert_eq!(client.quote_settlement(&booking_id, &101).expert_pay, 1_005);
+}
+
+#[test]
+fn test_invalid_pricing_schedules() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &100_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // Must start at zero
+    let pricing = soroban_sdk::vec![&env, PricingTier { threshold: 10, rate_per_second: 1 }];
+    let result = client.try_book_session_tiered(&user, &expert, &pricing, &100, &0, &GRACE_PERIOD);
+    assert_eq!(result, Err(Ok(VaultError::InvalidPricingSchedule)));
+
+    // Thresholds must increase
+    let pricing = soroban_sdk::vec![
+        &env,
+        PricingTier { threshold: 0, rate_per_second: 2 },
+        PricingTier { threshold: 0, rate_per_second: 1 },
+    ];
+    let result = client.try_book_session_tiered(&user, &expert, &pricing, &100, &0, &GRACE_PERIOD);
+    assert_eq!(result, Err(Ok(VaultError::InvalidPricingSchedule)));
+
+    // Rates must be positive
+    let pricing = soroban_sdk::vec![
+        &env,
+        PricingTier { threshold: 0, rate_per_second: 2 },
+        PricingTier { threshold: 50, rate_per_second: 0 },
+    ];
+    let result = client.try_book_session_tiered(&user, &expert, &pricing, &100, &0, &GRACE_PERIOD);
+    assert_eq!(result, Err(Ok(VaultError::InvalidPricingSchedule)));
+
+    // Overflowing deposits are rejected rather than wrapping
+    let pricing = soroban_sdk::vec![&env, PricingTier { threshold: 0, rate_per_second: i128::MAX }];
+    let result = client.try_book_session_tiered(&user, &expert, &pricing, &2, &0, &GRACE_PERIOD);
+    assert_eq!(result, Err(Ok(VaultError::InvalidAmount)));
+
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_finalization_window_enforced() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    env.ledger().set_timestamp(1_000);
+
+    // Session starts in an hour, 100 seconds long, 600 seconds of grace
+    let booking_id = client.book_session(&user, &expert, &10, &100, &4_600, &600, &None);
+    let booking = client.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.scheduled_start, 4_600);
+    assert_eq!(booking.expires_at, 5_300);
+
+    // Too early
+    let result = client.try_finalize_session(&booking_id, &50);
+    assert_eq!(result, Err(Ok(VaultError::SessionNotStarted)));
+
+    // Too late
+    env.ledger().set_timestamp(5_301);
+    let result = client.try_finalize_session(&booking_id, &50);
+    assert_eq!(result, Err(Ok(VaultError::BookingExpired)));
+
+    // Last second of the window is accepted
+    env.ledger().set_timestamp(5_300);
+    client.finalize_session(&booking_id, &50);
+    assert_eq!(token.balance(&expert), 500);
+}
+
+#[test]
+fn test_reclaim_right_after_window_passes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &200, &50, &None);
+
+    // Still inside the window
+    env.ledger().set_timestamp(350);
+    let result = client.try_reclaim_stale_session(&user, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::ReclaimTooEarly)));
+
+    // No fixed 24 hour wait once the window has passed
+    env.ledger().set_timestamp(351);
+    client.reclaim_stale_session(&user, &booking_id);
+    assert_eq!(token.balance(&user), 10_000);
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Reclaimed);
+}
+
+#[test]
+fn test_invalid_booking_schedule() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    env.ledger().set_timestamp(1_000);
+
+    // Start in the past
+    let result = client.try_book_session(&user, &expert, &10, &100, &999, &GRACE_PERIOD, &None);
+    assert_eq!(result, Err(Ok(VaultError::InvalidSchedule)));
+
+    // Grace period longer than a week
+    let result = client.try_book_session(&user, &expert, &10, &100, &1_000, &604_801, &None);
+    assert_eq!(result, Err(Ok(VaultError::InvalidSchedule)));
+
+    // Window overflowing the timestamp range
+    let result = client.try_book_session(&user, &expert, &10, &100, &(u64::MAX - 50), &0, &None);
+    assert_eq!(result, Err(Ok(VaultError::InvalidSchedule)));
+
+    assert_eq!(token.balance(&user), 10_000);
+}
+
+#[test]
+fn test_platform_fee_accrues_to_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // 10% platform fee
+    client.set_platform_fee(&1_000);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    let quote = client.quote_settlement(&booking_id, &50);
+    assert_eq!(quote.expert_pay, 450);
+    assert_eq!(quote.platform_fee, 50);
+    assert_eq!(quote.refund, 500);
+
+    client.finalize_session(&booking_id, &50);
+    assert_eq!(token.balance(&expert), 450);
+    assert_eq!(token.balance(&user), 9_500);
+    assert_eq!(client.get_fee_pool(), 50);
+    assert_eq!(token.balance(&client.address), 50);
+
+    // Cannot withdraw more than accrued
+    let result = client.try_withdraw_fees(&treasury, &51);
+    assert_eq!(result, Err(Ok(VaultError::InsufficientFees)));
+
+    client.withdraw_fees(&treasury, &50);
+    assert_eq!(token.balance(&treasury), 50);
+    assert_eq!(client.get_fee_pool(), 0);
+
+    // Fee above 100% is rejected
+    let result = client.try_set_platform_fee(&10_001);
+    assert_eq!(result, Err(Ok(VaultError::InvalidFee)));
+}
+
+#[test]
+fn test_keeper_expires_booking_with_bounty() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other_user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let keeper = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+    token.mint(&other_user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+    client.set_platform_fee(&1_000);
+    client.set_keeper_bounty(&20);
+
+    // Settle one booking to fund the fee pool with 100
+    let funded_id = client.book_session(&other_user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.finalize_session(&funded_id, &100);
+    assert_eq!(client.get_fee_pool(), 100);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    // Cannot expire while the window is open
+    let result = client.try_expire_booking(&keeper, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::BookingNotExpired)));
+
+    env.ledger().set_timestamp(100 + GRACE_PERIOD + 1);
+    client.expire_booking(&keeper, &booking_id);
+
+    assert_eq!(token.balance(&user), 10_000);
+    assert_eq!(token.balance(&keeper), 20);
+    assert_eq!(client.get_fee_pool(), 80);
+    assert_eq!(token.balance(&client.address), 80);
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Expired);
+
+    // Cannot expire twice or reclaim afterwards
+    let result = client.try_expire_booking(&keeper, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::BookingNotPending)));
+    let result = client.try_reclaim_stale_session(&user, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::BookingNotPending)));
+}
+
+#[test]
+fn test_keeper_bounty_limited_by_fee_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let keeper = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+    client.set_keeper_bounty(&20);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    // Empty fee pool: the booking still expires, without a bounty
+    env.ledger().set_timestamp(100 + GRACE_PERIOD + 1);
+    client.expire_booking(&keeper, &booking_id);
+
+    assert_eq!(token.balance(&user), 10_000);
+    assert_eq!(token.balance(&keeper), 0);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_milestone_engagement_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // A session booking and an engagement share the ID sequence and indexes
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    let milestones = soroban_sdk::vec![&env, 1_000_i128, 2_000_i128];
+    let engagement_id = client.create_engagement(&user, &expert, &milestones, &86_400);
+    assert_eq!(engagement_id, booking_id + 1);
+    assert_eq!(client.get_user_bookings(&user).len(), 2);
+    assert_eq!(client.get_expert_bookings(&expert).len(), 2);
+    assert_eq!(token.balance(&client.address), 4_000);
+
+    // Nothing to approve before submission
+    let result = client.try_approve_milestone(&user, &engagement_id);
+    assert_eq!(result, Err(Ok(MarketError::MilestoneNotSubmitted)));
+
+    client.submit_milestone(&expert, &engagement_id);
+    let result = client.try_submit_milestone(&expert, &engagement_id);
+    assert_eq!(result, Err(Ok(MarketError::MilestoneAlreadySubmitted)));
+
+    client.approve_milestone(&user, &engagement_id);
+    assert_eq!(token.balance(&expert), 1_000);
+
+    client.submit_milestone(&expert, &engagement_id);
+    client.approve_milestone(&user, &engagement_id);
+    assert_eq!(token.balance(&expert), 3_000);
+
+    let engagement = client.get_engagement(&engagement_id).unwrap();
+    assert_eq!(engagement.status, BookingStatus::Complete);
+    assert_eq!(engagement.released, 3_000);
+    assert_eq!(engagement.next_milestone, 2);
+}
+
+#[test]
+fn test_milestone_auto_approval_after_timeout() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let milestones = soroban_sdk::vec![&env, 1_000_i128, 2_000_i128];
+    let engagement_id = client.create_engagement(&user, &expert, &milestones, &86_400);
+
+    env.ledger().set_timestamp(1_000);
+    client.submit_milestone(&expert, &engagement_id);
+
+    // User stays silent
+    env.ledger().set_timestamp(1_000 + 86_399);
+    let result = client.try_auto_approve_milestone(&engagement_id);
+    assert_eq!(result, Err(Ok(MarketError::ApprovalTimeoutNotReached)));
+
+    env.ledger().set_timestamp(1_000 + 86_400);
+    client.auto_approve_milestone(&engagement_id);
+    assert_eq!(token.balance(&expert), 1_000);
+
+    // User cancels the remaining work and gets the unreleased amount back
+    client.cancel_engagement(&user, &engagement_id);
+    assert_eq!(token.balance(&user), 9_000);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(
+        client.get_engagement(&engagement_id).unwrap().status,
+        BookingStatus::Reclaimed
+    );
+}
+
+#[test]
+fn test_engagement_authorization_and_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // Empty and non-positive milestones are rejected
+    let result = client.try_create_engagement(&user, &expert, &soroban_sdk::vec![&env], &86_400);
+    assert_eq!(result, Err(Ok(MarketError::InvalidMilestones)));
+    let milestones = soroban_sdk::vec![&env, 1_000_i128, 0_i128];
+    let result = client.try_create_engagement(&user, &expert, &milestones, &86_400);
+    assert_eq!(result, Err(Ok(MarketError::InvalidMilestones)));
+
+    let milestones = soroban_sdk::vec![&env, 1_000_i128];
+    let engagement_id = client.create_engagement(&user, &expert, &milestones, &86_400);
+
+    // Only the expert submits, only the user approves
+    let result = client.try_submit_milestone(&user, &engagement_id);
+    assert_eq!(result, Err(Ok(MarketError::NotAuthorized)));
+    client.submit_milestone(&expert, &engagement_id);
+    let result = client.try_approve_milestone(&expert, &engagement_id);
+    assert_eq!(result, Err(Ok(MarketError::NotAuthorized)));
+
+    // Cannot cancel while a milestone awaits approval
+    let result = client.try_cancel_engagement(&user, &engagement_id);
+    assert_eq!(result, Err(Ok(MarketError::MilestoneAlreadySubmitted)));
+
+    let result = client.try_submit_milestone(&expert, &999);
+    assert_eq!(result, Err(Ok(MarketError::EngagementNotFound)));
+}
+
+#[test]
+fn test_question_answered_before_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let question_hash = BytesN::from_array(&env, &[1; 32]);
+    let answer_hash = BytesN::from_array(&env, &[2; 32]);
+    let question_id = client.ask_question(&user, &expert, &500, &question_hash, &86_400);
+
+    assert_eq!(token.balance(&client.address), 500);
+    assert_eq!(client.get_user_bookings(&user).get(0).unwrap(), question_id);
+
+    // Only the addressed expert can answer
+    let result = client.try_answer_question(&user, &question_id, &answer_hash);
+    assert_eq!(result, Err(Ok(MarketError::NotAuthorized)));
+
+    env.ledger().set_timestamp(86_400);
+    client.answer_question(&expert, &question_id, &answer_hash);
+
+    assert_eq!(token.balance(&expert), 500);
+    assert_eq!(token.balance(&client.address), 0);
+    let question = client.get_question(&question_id).unwrap();
+    assert_eq!(question.status, BookingStatus::Complete);
+    assert_eq!(question.answer_hash, Some(answer_hash.clone()));
+
+    // Cannot be answered twice
+    let result = client.try_answer_question(&expert, &question_id, &answer_hash);
+    assert_eq!(result, Err(Ok(MarketError::BookingNotPending)));
+}
+
+#[test]
+fn test_unanswered_question_refunded_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let keeper = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let question_hash = BytesN::from_array(&env, &[1; 32]);
+    let question_id = client.ask_question(&user, &expert, &500, &question_hash, &86_400);
+
+    let result = client.try_expire_question(&keeper, &question_id);
+    assert_eq!(result, Err(Ok(MarketError::BookingNotExpired)));
+
+    env.ledger().set_timestamp(86_401);
+
+    // Late answers are not paid
+    let answer_hash = BytesN::from_array(&env, &[2; 32]);
+    let result = client.try_answer_question(&expert, &question_id, &answer_hash);
+    assert_eq!(result, Err(Ok(MarketError::BookingExpired)));
+
+    client.expire_question(&keeper, &question_id);
+    assert_eq!(token.balance(&user), 10_000);
+    assert_eq!(token.balance(&expert), 0);
+    assert_eq!(client.get_question(&question_id).unwrap().status, BookingStatus::Expired);
+}
+
+#[test]
+fn test_invalid_question() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+    env.ledger().set_timestamp(1_000);
+
+    let question_hash = BytesN::from_array(&env, &[1; 32]);
+    let result = client.try_ask_question(&user, &expert, &0, &question_hash, &86_400);
+    assert_eq!(result, Err(Ok(MarketError::InvalidAmount)));
+
+    let result = client.try_ask_question(&user, &expert, &500, &question_hash, &1_000);
+    assert_eq!(result, Err(Ok(MarketError::InvalidSchedule)));
+
+    let answer_hash = BytesN::from_array(&env, &[2; 32]);
+    let result = client.try_answer_question(&expert, &999, &answer_hash);
+    assert_eq!(result, Err(Ok(MarketError::QuestionNotFound)));
+}
+
+fn create_registry<'a>(env: &'a Env, admin: &Address) -> IdentityRegistryContractClient<'a> {
+    let contract_id = env.register(IdentityRegistryContract, ());
+    let registry = IdentityRegistryContractClient::new(env, &contract_id);
+    registry.init(admin);
+    registry
+}
+
+#[test]
+fn test_bounty_split_between_verified_experts() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let poster = Address::generate(&env);
+    let expert1 = Address::generate(&env);
+    let expert2 = Address::generate(&env);
+    let expert3 = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&poster, &10_000);
+
+    let registry = create_registry(&env, &admin);
+    registry.batch_add_experts(&soroban_sdk::vec![
+        &env,
+        expert1.clone(),
+        expert2.clone(),
+        expert3.clone()
+    ]);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+    client.set_registry(&registry.address);
+
+    let question_hash = BytesN::from_array(&env, &[1; 32]);
+    let bounty_id = client.post_bounty(&poster, &900, &question_hash, &86_400);
+    assert_eq!(token.balance(&client.address), 900);
+
+    let index1 =
+        client.submit_bounty_answer(&expert1, &bounty_id, &BytesN::from_array(&env, &[2; 32]));
+    let index2 =
+        client.submit_bounty_answer(&expert2, &bounty_id, &BytesN::from_array(&env, &[3; 32]));
+    let index3 =
+        client.submit_bounty_answer(&expert3, &bounty_id, &BytesN::from_array(&env, &[4; 32]));
+    assert_eq!((index1, index2, index3), (0, 1, 2));
+
+    // One answer per expert
+    let result =
+        client.try_submit_bounty_answer(&expert1, &bounty_id, &BytesN::from_array(&env, &[5; 32]));
+    assert_eq!(result, Err(Ok(MarketError::AlreadySubmitted)));
+
+    // Pagination over submissions
+    let page = client.get_bounty_submissions(&bounty_id, &1, &5);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().expert, expert2);
+    assert_eq!(client.get_bounty_submissions(&bounty_id, &3, &5).len(), 0);
+
+    // Awards must cover the full bounty
+    let short = soroban_sdk::vec![&env, BountyAward { submission: 0, amount: 600 }];
+    let result = client.try_award_bounty(&poster, &bounty_id, &short);
+    assert_eq!(result, Err(Ok(MarketError::InvalidAwards)));
+
+    let awards = soroban_sdk::vec![
+        &env,
+        BountyAward { submission: 0, amount: 600 },
+        BountyAward { submission: 2, amount: 300 },
+    ];
+    client.award_bounty(&poster, &bounty_id, &awards);
+
+    assert_eq!(token.balance(&expert1), 600);
+    assert_eq!(token.balance(&expert2), 0);
+    assert_eq!(token.balance(&expert3), 300);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(client.get_bounty(&bounty_id).unwrap().status, BookingStatus::Complete);
+}
+
+#[test]
+fn test_bounty_rejects_unverified_expert() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let poster = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&poster, &10_000);
+
+    let registry = create_registry(&env, &admin);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let question_hash = BytesN::from_array(&env, &[1; 32]);
+    let bounty_id = client.post_bounty(&poster, &900, &question_hash, &86_400);
+    let answer_hash = BytesN::from_array(&env, &[2; 32]);
+
+    // Registry must be configured
+    let result = client.try_submit_bounty_answer(&expert, &bounty_id, &answer_hash);
+    assert_eq!(result, Err(Ok(MarketError::RegistryNotSet)));
+
+    client.set_registry(&registry.address);
+    let result = client.try_submit_bounty_answer(&expert, &bounty_id, &answer_hash);
+    assert_eq!(result, Err(Ok(MarketError::ExpertNotVerified)));
+
+    // Banned experts are rejected as well
+    registry.add_expert(&expert, &soroban_sdk::String::from_str(&env, "ipfs://expert"));
+    registry.ban_expert(&expert);
+    let result = client.try_submit_bounty_answer(&expert, &bounty_id, &answer_hash);
+    assert_eq!(result, Err(Ok(MarketError::ExpertNotVerified)));
+}
+
+#[test]
+fn test_unanswered_bounty_reclaimed_after_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let poster = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&poster, &10_000);
+
+    let registry = create_registry(&env, &admin);
+    registry.add_expert(&expert, &soroban_sdk::String::from_str(&env, "ipfs://expert"));
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+    client.set_registry(&registry.address);
+
+    let question_hash = BytesN::from_array(&env, &[1; 32]);
+    let unanswered_id = client.post_bounty(&poster, &500, &question_hash, &86_400);
+    let answered_id = client.post_bounty(&poster, &500, &question_hash, &86_400);
+    client.submit_bounty_answer(&expert, &answered_id, &BytesN::from_array(&env, &[2; 32]));
+
+    let result = client.try_reclaim_bounty(&poster, &unanswered_id);
+    assert_eq!(result, Err(Ok(MarketError::ReclaimTooEarly)));
+
+    env.ledger().set_timestamp(86_401);
+
+    // Late answers are rejected
+    let late_hash = BytesN::from_array(&env, &[2; 32]);
+    let result = client.try_submit_bounty_answer(&expert, &unanswered_id, &late_hash);
+    assert_eq!(result, Err(Ok(MarketError::BookingExpired)));
+
+    client.reclaim_bounty(&poster, &unanswered_id);
+    assert_eq!(token.balance(&poster), 9_500);
+    assert_eq!(client.get_bounty(&unanswered_id).unwrap().status, BookingStatus::Reclaimed);
+
+    // A bounty with answers must be awarded instead
+    let result = client.try_reclaim_bounty(&poster, &answered_id);
+    assert_eq!(result, Err(Ok(MarketError::BountyHasSubmissions)));
+}
+
+#[test]
+fn test_unawarded_bounty_split_after_award_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let poster = Address::generate(&env);
+    let expert1 = Address::generate(&env);
+    let expert2 = Address::generate(&env);
+    let expert3 = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&poster, &10_000);
+
+    let registry = create_registry(&env, &admin);
+    registry.batch_add_experts(&soroban_sdk::vec![
+        &env,
+        expert1.clone(),
+        expert2.clone(),
+        expert3.clone()
+    ]);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+    client.set_registry(&registry.address);
+
+    let question_hash = BytesN::from_array(&env, &[1; 32]);
+    let answered_id = client.post_bounty(&poster, &1_000, &question_hash, &86_400);
+    let unanswered_id = client.post_bounty(&poster, &500, &question_hash, &86_400);
+    for expert in [&expert1, &expert2, &expert3] {
+        client.submit_bounty_answer(expert, &answered_id, &BytesN::from_array(&env, &[2; 32]));
+    }
+
+    // The poster has 7 days after expiry to award
+    env.ledger().set_timestamp(86_400 + 604_800);
+    let result = client.try_settle_bounty(&answered_id);
+    assert_eq!(result, Err(Ok(MarketError::BookingNotExpired)));
+
+    // After that, awards close and anyone can split the bounty equally
+    env.ledger().set_timestamp(86_400 + 604_801);
+    let awards = soroban_sdk::vec![&env, BountyAward { submission: 0, amount: 1_000 }];
+    let result = client.try_award_bounty(&poster, &answered_id, &awards);
+    assert_eq!(result, Err(Ok(MarketError::BookingExpired)));
+
+    client.settle_bounty(&answered_id);
+    assert_eq!(token.balance(&expert1), 334);
+    assert_eq!(token.balance(&expert2), 333);
+    assert_eq!(token.balance(&expert3), 333);
+    assert_eq!(client.get_bounty(&answered_id).unwrap().status, BookingStatus::Complete);
+
+    let result = client.try_settle_bounty(&answered_id);
+    assert_eq!(result, Err(Ok(MarketError::BookingNotPending)));
+
+    // A bounty nobody answered goes back to the poster
+    client.settle_bounty(&unanswered_id);
+    assert_eq!(token.balance(&poster), 9_000);
+    assert_eq!(client.get_bounty(&unanswered_id).unwrap().status, BookingStatus::Reclaimed);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_solvent(&client, &token.address);
+}
+
+#[test]
+fn test_auction_outbid_refund_and_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let bidder1 = Address::generate(&env);
+    let bidder2 = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&bidder1, &10_000);
+    token.mint(&bidder2, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // 1-hour slot starting at t=10_000, bidding closes at t=5_000
+    let auction_id = client.create_auction(&expert, &3_600, &3_600, &10_000, &GRACE_PERIOD, &5_000);
+
+    let result = client.try_place_bid(&bidder1, &auction_id, &3_599);
+    assert_eq!(result, Err(Ok(MarketError::BidTooLow)));
+
+    client.place_bid(&bidder1, &auction_id, &4_000);
+    assert_eq!(token.balance(&bidder1), 6_000);
+
+    // Outbid deposit is refunded immediately
+    client.place_bid(&bidder2, &auction_id, &7_200);
+    assert_eq!(token.balance(&bidder1), 10_000);
+    assert_eq!(token.balance(&bidder2), 2_800);
+    assert_eq!(token.balance(&client.address), 7_200);
+
+    let result = client.try_place_bid(&bidder1, &auction_id, &7_200);
+    assert_eq!(result, Err(Ok(MarketError::BidTooLow)));
+
+    let result = client.try_settle_auction(&auction_id);
+    assert_eq!(result, Err(Ok(MarketError::AuctionNotEnded)));
+
+    env.ledger().set_timestamp(5_000);
+    let result = client.try_place_bid(&bidder1, &auction_id, &8_000);
+    assert_eq!(result, Err(Ok(MarketError::AuctionEnded)));
+
+    // Winning bid becomes a funded booking
+    let booking_id = client.settle_auction(&auction_id).unwrap();
+    let booking = client.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.user, bidder2);
+    assert_eq!(booking.expert, expert);
+    assert_eq!(booking.total_deposit, 7_200);
+    assert_eq!(booking.rate_per_second, 2);
+    assert_eq!(booking.scheduled_start, 10_000);
+    assert_eq!(booking.status, BookingStatus::Pending);
+    assert_eq!(client.get_user_bookings(&bidder2).get(0).unwrap(), booking_id);
+
+    let auction = client.get_auction(&auction_id).unwrap();
+    assert_eq!(auction.status, BookingStatus::Complete);
+    assert_eq!(auction.booking_id, Some(booking_id));
+
+    // The booking settles like any other
+    env.ledger().set_timestamp(10_000);
+    client.finalize_session(&booking_id, &1_800);
+    assert_eq!(token.balance(&expert), 3_600);
+    assert_eq!(token.balance(&bidder2), 2_800 + 3_600);
+}
+
+#[test]
+fn test_auction_without_bids_expires() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let token = Address::generate(&env);
+
+    let client = create_client(&env);
+    client.init(&admin, &token, &oracle);
+
+    // Bidding must close before the slot starts
+    let result =
+        client.try_create_auction(&expert, &3_600, &3_600, &10_000, &GRACE_PERIOD, &10_001);
+    assert_eq!(result, Err(Ok(MarketError::InvalidSchedule)));
+
+    // Reserve must cover at least one token per second
+    let result = client.try_create_auction(&expert, &100, &3_600, &10_000, &GRACE_PERIOD, &5_000);
+    assert_eq!(result, Err(Ok(MarketError::InvalidAmount)));
+
+    let auction_id = client.create_auction(&expert, &3_600, &3_600, &10_000, &GRACE_PERIOD, &5_000);
+
+    env.ledger().set_timestamp(5_000);
+    assert_eq!(client.settle_auction(&auction_id), None);
+    assert_eq!(client.get_auction(&auction_id).unwrap().status, BookingStatus::Expired);
+
+    let result = client.try_settle_auction(&auction_id);
+    assert_eq!(result, Err(Ok(MarketError::BookingNotPending)));
+}
+
+fn sign_quote(env: &Env, vault: &Address, signer: &SigningKey, quote: &Quote) -> BytesN<64> {
+    let payload = (vault.clone(), quote.clone()).to_xdr(env);
+    let mut message = std::vec![0u8; payload.len() as usize];
+    payload.copy_into_slice(&mut message);
+    BytesN::from_array(env, &signer.sign(&message).to_bytes())
+}
+
+#[test]
+fn test_book_with_signed_quote() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let signer = SigningKey::from_bytes(&[7; 32]);
+    let public_key = BytesN::from_array(&env, &signer.verifying_key().to_bytes());
+    client.register_signing_key(&expert, &public_key);
+
+    let quote = Quote {
+        expert: expert.clone(),
+        user: user.clone(),
+        rate_per_second: 3,
+        max_duration: 600,
+        expires_at: 1_000,
+        nonce: 1,
+    };
+    let signature = sign_quote(&env, &client.address, &signer, &quote);
+
+    let booking_id = client.book_with_quote(&user, &quote, &signature, &0, &GRACE_PERIOD);
+    let booking = client.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.expert, expert);
+    assert_eq!(booking.rate_per_second, 3);
+    assert_eq!(booking.total_deposit, 1_800);
+    assert_eq!(token.balance(&client.address), 1_800);
+
+    // Replaying the same quote is blocked by its nonce
+    let result = client.try_book_with_quote(&user, &quote, &signature, &0, &GRACE_PERIOD);
+    assert_eq!(result, Err(Ok(VaultError::QuoteAlreadyUsed)));
+}
+
+#[test]
+fn test_signed_quote_restrictions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other_user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let signer = SigningKey::from_bytes(&[7; 32]);
+    let quote = Quote {
+        expert: expert.clone(),
+        user: user.clone(),
+        rate_per_second: 3,
+        max_duration: 600,
+        expires_at: 1_000,
+        nonce: 1,
+    };
+    let signature = sign_quote(&env, &client.address, &signer, &quote);
+
+    // Expert has not registered a key
+    let result = client.try_book_with_quote(&user, &quote, &signature, &0, &GRACE_PERIOD);
+    assert_eq!(result, Err(Ok(VaultError::SigningKeyNotSet)));
+
+    let public_key = BytesN::from_array(&env, &signer.verifying_key().to_bytes());
+    client.register_signing_key(&expert, &public_key);
+
+    // Quote is bound to the intended user
+    let result = client.try_book_with_quote(&other_user, &quote, &signature, &0, &GRACE_PERIOD);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+
+    // Tampered terms fail signature verification
+    let mut tampered = quote.clone();
+    tampered.rate_per_second = 1;
+    let result = client.try_book_with_quote(&user, &tampered, &signature, &0, &GRACE_PERIOD);
+    assert!(result.is_err());
+
+    // Expired quote
+    env.ledger().set_timestamp(1_001);
+    let result = client.try_book_with_quote(&user, &quote, &signature, &1_001, &GRACE_PERIOD);
+    assert_eq!(result, Err(Ok(VaultError::QuoteExpired)));
+
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_voucher_half_off_first_session() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+    token.mint(&admin, &5_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // 50% off, one redemption per user, funded by the admin
+    let campaign_id =
+        client.create_voucher_campaign(&VoucherDiscount::Bps(5_000), &5_000, &1, &1_000, &false);
+    assert_eq!(token.balance(&admin), 0);
+
+    // Deposit of 1_000: user pays 500, campaign covers 500
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &10,
+        &100,
+        &0,
+        &GRACE_PERIOD,
+        &Some(campaign_id),
+    );
+    assert_eq!(token.balance(&user), 9_500);
+    assert_eq!(client.get_voucher_campaign(&campaign_id).unwrap().budget, 4_500);
+
+    let booking = client.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.total_deposit, 1_000);
+    assert_eq!(booking.funding.subsidy, 500);
+
+    // Second redemption by the same user is refused
+    let result =
+        client.try_book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &Some(campaign_id));
+    assert_eq!(result, Err(Ok(VaultError::VoucherLimitReached)));
+
+    // 40 seconds used: expert is paid in full, the unused 600 is split between campaign and user
+    client.finalize_session(&booking_id, &40);
+    assert_eq!(token.balance(&expert), 400);
+    assert_eq!(token.balance(&user), 9_800);
+    assert_eq!(client.get_voucher_campaign(&campaign_id).unwrap().budget, 4_800);
+    assert_eq!(token.balance(&client.address), 4_800);
+}
+
+#[test]
+fn test_voucher_refund_restores_redemption() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+    token.mint(&admin, &1_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let campaign_id =
+        client.create_voucher_campaign(&VoucherDiscount::Fixed(300), &1_000, &1, &1_000, &false);
+
+    let booking_id = client.book_session(
+        &user,
+        &expert,
+        &10,
+        &100,
+        &0,
+        &GRACE_PERIOD,
+        &Some(campaign_id),
+    );
+    assert_eq!(token.balance(&user), 9_300);
+
+    // Rejection returns the subsidy to the campaign and the voucher to the user
+    client.reject_session(&expert, &booking_id, &RejectionReason::Unavailable, &None);
+    assert_eq!(token.balance(&user), 10_000);
+    assert_eq!(client.get_voucher_campaign(&campaign_id).unwrap().budget, 1_000);
+
+    client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &Some(campaign_id));
+    assert_eq!(token.balance(&user), 9_300);
+}
+
+#[test]
+fn test_voucher_campaign_limits_and_close() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // Build up a fee pool of 100 from a fully used session
+    client.set_platform_fee(&1_000);
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.finalize_session(&booking_id, &100);
+    assert_eq!(client.get_fee_pool(), 100);
+
+    // Budget cannot exceed the fee pool
+    let result =
+        client.try_create_voucher_campaign(&VoucherDiscount::Bps(5_000), &101, &5, &1_000, &true);
+    assert_eq!(result, Err(Ok(VaultError::InsufficientFees)));
+
+    let result =
+        client.try_create_voucher_campaign(&VoucherDiscount::Bps(10_001), &100, &5, &1_000, &true);
+    assert_eq!(result, Err(Ok(VaultError::InvalidAmount)));
+
+    let campaign_id =
+        client.create_voucher_campaign(&VoucherDiscount::Bps(5_000), &100, &5, &1_000, &true);
+    assert_eq!(client.get_fee_pool(), 0);
+
+    // Subsidy of 500 is more than the remaining budget
+    let result =
+        client.try_book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &Some(campaign_id));
+    assert_eq!(result, Err(Ok(VaultError::VoucherBudgetExhausted)));
+
+    client.book_session(&user, &expert, &1, &100, &0, &GRACE_PERIOD, &Some(campaign_id));
+    assert_eq!(client.get_voucher_campaign(&campaign_id).unwrap().budget, 50);
+
+    // Expired campaign
+    env.ledger().set_timestamp(1_001);
+    let result = client.try_book_session(
+        &user,
+        &expert,
+        &1,
+        &100,
+        &1_001,
+        &GRACE_PERIOD,
+        &Some(campaign_id),
+    );
+    assert_eq!(result, Err(Ok(VaultError::VoucherExpired)));
+
+    // Closing returns the remaining budget to the fee pool
+    client.close_voucher_campaign(&campaign_id);
+    assert_eq!(client.get_fee_pool(), 50);
+    let campaign = client.get_voucher_campaign(&campaign_id).unwrap();
+    assert!(!campaign.active);
+    assert_eq!(campaign.budget, 0);
+
+    let result =
+        client.try_book_session(&user, &expert, &10, &100, &1_001, &GRACE_PERIOD, &Some(99));
+    assert_eq!(result, Err(Ok(VaultError::VoucherNotFound)));
+}
+
+#[test]
+fn test_tip_completed_session() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other_user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+    token.mint(&other_user, &1_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    // Pending sessions cannot be tipped
+    let result = client.try_tip(&user, &booking_id, &50);
+    assert_eq!(result, Err(Ok(VaultError::BookingNotComplete)));
+
+    client.finalize_session(&booking_id, &100);
+    assert_eq!(token.balance(&expert), 1_000);
+
+    // Only the booking's user can tip, and only positive amounts
+    let result = client.try_tip(&other_user, &booking_id, &50);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+    let result = client.try_tip(&user, &booking_id, &0);
+    assert_eq!(result, Err(Ok(VaultError::InvalidAmount)));
+
+    client.tip(&user, &booking_id, &50);
+    client.tip(&user, &booking_id, &25);
+    assert_eq!(token.balance(&expert), 1_075);
+    assert_eq!(token.balance(&user), 8_925);
+    assert_eq!(client.get_booking(&booking_id).unwrap().total_tips, 75);
+}
+
+#[test]
+fn test_retainer_recurring_charges_with_rollover() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // User pre-authorizes three periods of 1_000
+    let token_client = token::Client::new(&env, &token.address);
+    token_client.approve(&user, &client.address, &3_000, &1_000);
+
+    // 1_000 per 30-day period for 10 hours, unused time rolls over
+    let period = 2_592_000_u64;
+    let retainer_id = client.create_retainer(&user, &expert, &1_000, &period, &36_000, &true);
+    assert_eq!(token.balance(&user), 9_000);
+    assert_eq!(token.balance(&client.address), 1_000);
+    assert_eq!(token.balance(&expert), 0);
+
+    // Oracle records 6 hours of calls; the expert is paid for them out of escrow
+    client.record_retainer_usage(&retainer_id, &21_600);
+    assert_eq!(token.balance(&expert), 600);
+    let result = client.try_record_retainer_usage(&retainer_id, &14_401);
+    assert_eq!(result, Err(Ok(MarketError::AllotmentExceeded)));
+
+    // Next period cannot be charged early
+    let result = client.try_charge_period(&retainer_id);
+    assert_eq!(result, Err(Ok(MarketError::PeriodNotElapsed)));
+
+    // Anyone can charge once the period is over; 4 unused hours roll over
+    env.ledger().set_timestamp(period);
+    client.charge_period(&retainer_id);
+    let retainer = client.get_retainer(&retainer_id).unwrap();
+    assert_eq!(retainer.available_seconds, 50_400);
+    assert_eq!(retainer.escrow, 1_400);
+    assert_eq!(retainer.period_end, 2 * period);
+    assert_eq!(token.balance(&user), 8_000);
+    assert_eq!(token.balance(&expert), 600);
+    assert_eq!(token_client.allowance(&user, &client.address), 1_000);
+    assert_solvent(&client, &token.address);
+
+    // Another idle period: 86_400 seconds are available, holding 2_400
+    env.ledger().set_timestamp(2 * period);
+    client.charge_period(&retainer_id);
+    assert_eq!(client.get_retainer(&retainer_id).unwrap().available_seconds, 86_400);
+
+    // Rollover is capped at two allotments; the allowance is now used up, so the
+    // retainer lapses and refunds the 400 held for the expired time plus the 2_000 kept
+    env.ledger().set_timestamp(3 * period);
+    client.charge_period(&retainer_id);
+    let retainer = client.get_retainer(&retainer_id).unwrap();
+    assert_eq!(retainer.status, BookingStatus::Expired);
+    assert_eq!(retainer.available_seconds, 0);
+    assert_eq!(retainer.escrow, 0);
+    assert_eq!(token.balance(&user), 9_400);
+    assert_eq!(token.balance(&expert), 600);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_solvent(&client, &token.address);
+
+    let result = client.try_charge_period(&retainer_id);
+    assert_eq!(result, Err(Ok(MarketError::RetainerNotActive)));
+}
+
+#[test]
+fn test_retainer_cancel_at_period_end() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let token_client = token::Client::new(&env, &token.address);
+
+    // No allowance yet
+    let result = client.try_create_retainer(&user, &expert, &500, &1_000, &600, &false);
+    assert_eq!(result, Err(Ok(MarketError::InsufficientAllowance)));
+
+    token_client.approve(&user, &client.address, &1_000, &1_000);
+    let retainer_id = client.create_retainer(&user, &expert, &500, &1_000, &600, &false);
+
+    // Without rollover the allotment resets each period and unused time is refunded:
+    // 100 of 600 seconds pays 83, the other 417 returns to the user
+    client.record_retainer_usage(&retainer_id, &100);
+    env.ledger().set_timestamp(1_000);
+    client.charge_period(&retainer_id);
+    assert_eq!(client.get_retainer(&retainer_id).unwrap().available_seconds, 600);
+    assert_eq!(token.balance(&user), 9_417);
+    assert_eq!(token.balance(&expert), 83);
+
+    // Only the parties can cancel
+    let result = client.try_cancel_retainer(&stranger, &retainer_id);
+    assert_eq!(result, Err(Ok(MarketError::NotAuthorized)));
+
+    // Expert cancels; time stays usable until the period end
+    client.cancel_retainer(&expert, &retainer_id);
+    client.record_retainer_usage(&retainer_id, &300);
+
+    // The period-end charge closes the retainer instead of renewing it
+    env.ledger().set_timestamp(2_000);
+    client.charge_period(&retainer_id);
+    let retainer = client.get_retainer(&retainer_id).unwrap();
+    assert_eq!(retainer.status, BookingStatus::Complete);
+    assert_eq!(retainer.available_seconds, 0);
+    assert_eq!(token.balance(&user), 9_667);
+    assert_eq!(token.balance(&expert), 333);
+    assert_solvent(&client, &token.address);
+
+    let result = client.try_charge_period(&retainer_id);
+    assert_eq!(result, Err(Ok(MarketError::RetainerNotActive)));
+}
+
+#[test]
+fn test_book_session_for_uses_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let token_client = token::Client::new(&env, &token.address);
+    token_client.approve(&user, &client.address, &1_500, &1_000);
+
+    // The user signs only the vault call; no nested token authorization
+    let args = (
+        user.clone(),
+        expert.clone(),
+        10_i128,
+        100_u64,
+        0_u64,
+        GRACE_PERIOD,
+        None::<u64>,
+    );
+    let user_intent = MockAuth {
+        address: &user,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "book_session_for",
+            args: args.into_val(&env),
+            sub_invokes: &[],
+        },
+    };
+
+    // The same narrow authorization is not enough for a direct transfer
+    env.mock_auths(&[MockAuth {
+        address: &user,
+        invoke: &MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "book_session",
+            args: args.into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    let result = client.try_book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    assert!(result.is_err());
+
+    env.mock_auths(&[user_intent]);
+    let booking_id = client.book_session_for(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    assert_eq!(token.balance(&user), 9_000);
+    assert_eq!(token.balance(&client.address), 1_000);
+    assert_eq!(token_client.allowance(&user, &client.address), 500);
+    assert_eq!(client.get_booking(&booking_id).unwrap().total_deposit, 1_000);
+
+    // Remaining allowance does not cover another booking
+    env.mock_all_auths();
+    let result = client.try_book_session_for(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    assert_eq!(result, Err(Ok(VaultError::InsufficientAllowance)));
+}
+
+#[test]
+fn test_budget_pool_booking_and_refunds() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&employer, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // Weekly limits on a pool of 5_000
+    let pool_id = client.create_pool(&employer, &604_800);
+    client.deposit_to_pool(&employer, &pool_id, &5_000);
+    client.set_pool_beneficiary(&employer, &pool_id, &employee, &1_500);
+    client.set_pool_expert(&employer, &pool_id, &expert, &true);
+    assert_eq!(token.balance(&employer), 5_000);
+
+    let booking_id =
+        client.book_session_from_pool(&employee, &pool_id, &expert, &10, &100, &0, &GRACE_PERIOD);
+    assert_eq!(token.balance(&employee), 0);
+    assert_eq!(client.get_pool(&pool_id).unwrap().balance, 4_000);
+    assert_eq!(client.get_pool_beneficiary(&pool_id, &employee).unwrap().spent, 1_000);
+
+    // The unused part of the deposit returns to the pool, not the employee
+    client.finalize_session(&booking_id, &30);
+    assert_eq!(token.balance(&expert), 300);
+    assert_eq!(token.balance(&employee), 0);
+    assert_eq!(client.get_pool(&pool_id).unwrap().balance, 4_700);
+    assert_eq!(client.get_pool_beneficiary(&pool_id, &employee).unwrap().spent, 300);
+
+    // Rejection refunds the whole deposit to the pool
+    let booking_id =
+        client.book_session_from_pool(&employee, &pool_id, &expert, &10, &100, &0, &GRACE_PERIOD);
+    client.reject_session(&expert, &booking_id, &RejectionReason::Unavailable, &None);
+    assert_eq!(client.get_pool(&pool_id).unwrap().balance, 4_700);
+    assert_eq!(token.balance(&client.address), 4_700);
+
+    // Owner withdraws unspent funds
+    client.withdraw_from_pool(&employer, &pool_id, &4_000);
+    assert_eq!(token.balance(&employer), 9_000);
+    let result = client.try_withdraw_from_pool(&employer, &pool_id, &701);
+    assert_eq!(result, Err(Ok(VaultError::InsufficientBalance)));
+}
+
+#[test]
+fn test_budget_pool_limits_and_allowlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let other_expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&employer, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let period = 604_800_u64;
+    let pool_id = client.create_pool(&employer, &period);
+    client.deposit_to_pool(&employer, &pool_id, &10_000);
+    client.set_pool_beneficiary(&employer, &pool_id, &employee, &1_500);
+    client.set_pool_expert(&employer, &pool_id, &expert, &true);
+
+    // Only the owner manages the pool
+    let result = client.try_set_pool_beneficiary(&outsider, &pool_id, &outsider, &1_000);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+
+    // Non-beneficiaries and non-allowlisted experts are refused
+    let result =
+        client.try_book_session_from_pool(&outsider, &pool_id, &expert, &10, &100, &0, &0);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+    let result =
+        client.try_book_session_from_pool(&employee, &pool_id, &other_expert, &10, &100, &0, &0);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+
+    // Second booking would take the employee past their weekly limit
+    client.book_session_from_pool(&employee, &pool_id, &expert, &10, &100, &0, &GRACE_PERIOD);
+    let result =
+        client.try_book_session_from_pool(&employee, &pool_id, &expert, &10, &100, &0, &0);
+    assert_eq!(result, Err(Ok(VaultError::SpendingLimitExceeded)));
+
+    // The limit resets in the next period
+    env.ledger().set_timestamp(period);
+    client.book_session_from_pool(&employee, &pool_id, &expert, &10, &100, &period, &0);
+    let entry = client.get_pool_beneficiary(&pool_id, &employee).unwrap();
+    assert_eq!(entry.spent, 1_000);
+    assert_eq!(entry.period_start, period);
+    assert_eq!(client.get_pool(&pool_id).unwrap().balance, 8_000);
+
+    let result = client.try_deposit_to_pool(&employer, &99, &100);
+    assert_eq!(result, Err(Ok(VaultError::PoolNotFound)));
+}
+
+#[test]
+fn test_stream_withdraw_accrued_and_stop() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+    client.set_platform_fee(&1_000);
+
+    // 10 per second for up to 100 seconds, starting at t=100
+    let booking_id = client.book_stream(&user, &expert, &10, &100, &100);
+    assert_eq!(token.balance(&user), 9_000);
+
+    // Nothing accrues before the start
+    env.ledger().set_timestamp(50);
+    assert_eq!(client.withdraw_accrued(&expert, &booking_id), 0);
+
+    // 30 seconds in: 300 gross, 270 to the expert after the 10% fee
+    env.ledger().set_timestamp(130);
+    assert_eq!(client.withdraw_accrued(&expert, &booking_id), 300);
+    assert_eq!(token.balance(&expert), 270);
+    assert_eq!(client.get_fee_pool(), 30);
+
+    // The oracle path is closed for streams
+    let result = client.try_finalize_session(&booking_id, &30);
+    assert_eq!(result, Err(Ok(VaultError::InvalidBookingMode)));
+
+    // User stops at 45 seconds and gets the unstreamed 550 back immediately
+    env.ledger().set_timestamp(145);
+    client.stop_stream(&user, &booking_id);
+    assert_eq!(token.balance(&user), 9_550);
+
+    // Accrual is frozen; the expert collects the last 150
+    env.ledger().set_timestamp(200);
+    assert_eq!(client.withdraw_accrued(&expert, &booking_id), 150);
+    assert_eq!(token.balance(&expert), 405);
+    assert_eq!(token.balance(&client.address), 45);
+
+    let booking = client.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Complete);
+    assert_eq!(booking.withdrawn, 450);
+}
+
+#[test]
+fn test_stream_runs_to_max_duration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let booking_id = client.book_stream(&user, &expert, &10, &100, &0);
+
+    // Only the user stops and only the expert withdraws
+    let result = client.try_stop_stream(&expert, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+    let result = client.try_withdraw_accrued(&user, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+
+    // Accrual is capped at max_duration, and the stream is not reclaimable or expirable
+    env.ledger().set_timestamp(500);
+    let result = client.try_reclaim_stale_session(&user, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::InvalidBookingMode)));
+    let result = client.try_expire_booking(&admin, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::InvalidBookingMode)));
+
+    assert_eq!(client.withdraw_accrued(&expert, &booking_id), 1_000);
+    assert_eq!(token.balance(&expert), 1_000);
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Complete);
+
+    // Non-stream bookings have no accrual
+    let booking_id = client.book_session(&user, &expert, &10, &100, &500, &GRACE_PERIOD, &None);
+    let result = client.try_withdraw_accrued(&expert, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::InvalidBookingMode)));
+}
+
+#[test]
+fn test_stopped_stream_cannot_be_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // Stopped with 300 accrued and unwithdrawn: the stream closes right away
+    let booking_id = client.book_stream(&user, &expert, &10, &100, &0);
+    env.ledger().set_timestamp(30);
+    client.stop_stream(&user, &booking_id);
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Complete);
+
+    // Rejecting it would refund the full deposit on top of the stop refund
+    let result = client.try_reject_session(
+        &expert,
+        &booking_id,
+        &RejectionReason::Unavailable,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(VaultError::InvalidBookingMode)));
+    assert_solvent(&client, &token.address);
+
+    // The expert still collects the accrual once, and only once
+    assert_eq!(client.withdraw_accrued(&expert, &booking_id), 300);
+    let result = client.try_withdraw_accrued(&expert, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::BookingNotPending)));
+    assert_eq!(token.balance(&user), 9_700);
+    assert_solvent(&client, &token.address);
+
+    // A stop before anything accrues is a cancellation, not a completed session
+    let booking_id = client.book_stream(&user, &expert, &10, &100, &100);
+    client.stop_stream(&user, &booking_id);
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Reclaimed);
+    assert_eq!(token.balance(&user), 9_700);
+    assert_solvent(&client, &token.address);
+
+    let stats = client.get_expert_stats(&expert);
+    assert_eq!(stats.completed_sessions, 1);
+    assert_eq!(stats.seconds_billed, 30);
+    assert_eq!(stats.reclaims, 1);
+}
+
+#[test]
+fn test_solvency_invariant_across_booking_paths() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let employer = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &100_000);
+    token.mint(&admin, &2_000);
+    token.mint(&employer, &3_000);
+    let vault_token = token.address.clone();
+
+    let client = create_client(&env);
+    client.init(&admin, &vault_token, &oracle);
+    client.set_platform_fee(&1_000);
+    client.set_keeper_bounty(&5);
+    assert_solvent(&client, &vault_token);
+
+    // Oracle settlement
+    let b1 = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    assert_solvent(&client, &vault_token);
+    client.finalize_session(&b1, &40);
+    assert_solvent(&client, &vault_token);
+
+    // Rejection, reclaim and keeper expiry
+    let b2 = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.reject_session(&expert, &b2, &RejectionReason::Unavailable, &None);
+    assert_solvent(&client, &vault_token);
+
+    let b3 = client.book_session(&user, &expert, &10, &100, &0, &0, &None);
+    let b4 = client.book_session(&user, &expert, &10, &100, &0, &0, &None);
+    assert_solvent(&client, &vault_token);
+    env.ledger().set_timestamp(101);
+    client.reclaim_stale_session(&user, &b3);
+    assert_solvent(&client, &vault_token);
+    client.expire_booking(&keeper, &b4);
+    assert_solvent(&client, &vault_token);
+
+    // Voucher-subsidized booking, then the campaign closes
+    let campaign_id =
+        client.create_voucher_campaign(&VoucherDiscount::Bps(5_000), &2_000, &1, &10_000, &false);
+    assert_solvent(&client, &vault_token);
+    let b5 = client.book_session(&user, &expert, &10, &100, &101, &0, &Some(campaign_id));
+    assert_solvent(&client, &vault_token);
+    client.close_voucher_campaign(&campaign_id);
+    assert_solvent(&client, &vault_token);
+    client.finalize_session(&b5, &30);
+    assert_solvent(&client, &vault_token);
+
+    // Budget pool booking and owner withdrawal
+    let pool_id = client.create_pool(&employer, &604_800);
+    client.deposit_to_pool(&employer, &pool_id, &3_000);
+    client.set_pool_beneficiary(&employer, &pool_id, &user, &5_000);
+    client.set_pool_expert(&employer, &pool_id, &expert, &true);
+    assert_solvent(&client, &vault_token);
+    let b6 = client.book_session_from_pool(&user, &pool_id, &expert, &10, &100, &101, &0);
+    assert_solvent(&client, &vault_token);
+    client.finalize_session(&b6, &20);
+    assert_solvent(&client, &vault_token);
+    client.withdraw_from_pool(&employer, &pool_id, &1_000);
+    assert_solvent(&client, &vault_token);
+
+    // Stream: accrued pay moves to earnings when stopped, then out on withdrawal
+    let s1 = client.book_stream(&user, &expert, &10, &100, &101);
+    env.ledger().set_timestamp(131);
+    client.withdraw_accrued(&expert, &s1);
+    assert_solvent(&client, &vault_token);
+    env.ledger().set_timestamp(151);
+    client.stop_stream(&user, &s1);
+    assert_eq!(client.get_solvency(&vault_token).earnings, 200);
+    assert_solvent(&client, &vault_token);
+    client.withdraw_accrued(&expert, &s1);
+    assert_eq!(client.get_solvency(&vault_token).earnings, 0);
+    assert_solvent(&client, &vault_token);
+
+    // Fee withdrawal empties the pool of fees
+    client.withdraw_fees(&treasury, &client.get_fee_pool());
+    assert_solvent(&client, &vault_token);
+
+    // Only the budget pool's balance is still held
+    let report = client.get_solvency(&vault_token);
+    assert_eq!(report.locked, client.get_pool(&pool_id).unwrap().balance);
+    assert_eq!(report.fees, 0);
+}
+
+#[test]
+fn test_solvency_invariant_for_escrow_products() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &100_000);
+    token.mint(&bidder, &10_000);
+    let vault_token = token.address.clone();
+
+    let client = create_client(&env);
+    client.init(&admin, &vault_token, &oracle);
+    client.set_platform_fee(&500);
+
+    // Milestone engagement: one release, then cancel the rest
+    let milestones = Vec::from_array(&env, [300_i128, 700]);
+    let engagement_id = client.create_engagement(&user, &expert, &milestones, &86_400);
+    assert_solvent(&client, &vault_token);
+    client.submit_milestone(&expert, &engagement_id);
+    client.approve_milestone(&user, &engagement_id);
+    assert_solvent(&client, &vault_token);
+    client.cancel_engagement(&user, &engagement_id);
+    assert_solvent(&client, &vault_token);
+
+    // Questions: one answered, one expired
+    let hash = BytesN::from_array(&env, &[1; 32]);
+    let q1 = client.ask_question(&user, &expert, &200, &hash, &1_000);
+    let q2 = client.ask_question(&user, &expert, &200, &hash, &1_000);
+    assert_solvent(&client, &vault_token);
+    client.answer_question(&expert, &q1, &hash);
+    assert_solvent(&client, &vault_token);
+
+    // Bounty posted and reclaimed without answers
+    let bounty_id = client.post_bounty(&user, &500, &hash, &1_000);
+    assert_solvent(&client, &vault_token);
+
+    // Auction: outbid, settled into a booking, then finalized
+    let auction_id = client.create_auction(&expert, &100, &100, &2_000, &GRACE_PERIOD, &1_500);
+    client.place_bid(&user, &auction_id, &150);
+    assert_solvent(&client, &vault_token);
+    client.place_bid(&bidder, &auction_id, &250);
+    assert_solvent(&client, &vault_token);
+
+    env.ledger().set_timestamp(1_001);
+    client.expire_question(&keeper, &q2);
+    assert_solvent(&client, &vault_token);
+    client.reclaim_bounty(&user, &bounty_id);
+    assert_solvent(&client, &vault_token);
+
+    env.ledger().set_timestamp(2_000);
+    let booking_id = client.settle_auction(&auction_id).unwrap();
+    assert_solvent(&client, &vault_token);
+    client.finalize_session(&booking_id, &50);
+    assert_solvent(&client, &vault_token);
+    assert_eq!(client.get_solvency(&vault_token).locked, 0);
+
+    // Tokens sent straight to the vault show up as surplus
+    token.mint(&client.address, &77);
+    assert_eq!(client.get_solvency(&vault_token).surplus, 77);
+
+    // Other tokens carry no liabilities
+    let other = create_token_contract(&env, &token_admin);
+    other.mint(&client.address, &40);
+    let report = client.get_solvency(&other.address);
+    assert_eq!(report.locked + report.fees + report.earnings, 0);
+    assert_eq!(report.surplus, 40);
+}
+
+#[test]
+fn test_sweep_only_moves_surplus() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let rescuer = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+    client.set_platform_fee(&1_000);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    let done_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.finalize_session(&done_id, &100);
+
+    // Escrow and fees are not sweepable
+    let result = client.try_sweep(&token.address, &rescuer);
+    assert_eq!(result, Err(Ok(VaultError::InsufficientBalance)));
+
+    // Tokens sent to the vault by mistake are
+    token.mint(&client.address, &250);
+    assert_eq!(client.sweep(&token.address, &rescuer), 250);
+    assert_eq!(token.balance(&rescuer), 250);
+    assert_solvent(&client, &token.address);
+
+    // Other tokens are swept in full
+    let stray = create_token_contract(&env, &token_admin);
+    stray.mint(&client.address, &40);
+    assert_eq!(client.sweep(&stray.address, &rescuer), 40);
+    assert_eq!(stray.balance(&client.address), 0);
+
+    // The booking still settles from its untouched escrow
+    client.finalize_session(&booking_id, &100);
+    assert_eq!(token.balance(&expert), 1_800);
+    assert_solvent(&client, &token.address);
+}
+
+#[test]
+fn test_finalize_event_carries_participants_and_version() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let booking_id =
+        client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.finalize_session(&booking_id, &50);
+
+    // Find the lifecycle event among the token transfer events
+    let events = env.events().all();
+    let event = events
+        .iter()
+        .find(|e| {
+            let name: Result<Symbol, _> = e.1.get(0).unwrap().try_into_val(&env);
+            e.0 == client.address && name == Ok(Symbol::new(&env, "finalized"))
+        })
+        .unwrap();
+
+    // Topics: (name, booking ID, user, expert)
+    let topic_id: u64 = event.1.get(1).unwrap().try_into_val(&env).unwrap();
+    let topic_user: Address = event.1.get(2).unwrap().try_into_val(&env).unwrap();
+    let topic_expert: Address = event.1.get(3).unwrap().try_into_val(&env).unwrap();
+    assert_eq!(topic_id, booking_id);
+    assert_eq!(topic_user, user);
+    assert_eq!(topic_expert, expert);
+
+    let data: SessionFinalizedEvent = event.2.try_into_val(&env).unwrap();
+    assert_eq!(data.version, EVENT_VERSION);
+    assert_eq!(data.booking_id, booking_id);
+    assert_eq!(data.actual_duration, 50);
+    assert_eq!(data.total_cost, 500);
+    assert_eq!(data.expert_pay + data.platform_fee, data.total_cost);
+    assert_eq!(data.refund, 500);
+}
+
+#[test]
+fn test_market_event_leaves_missing_participant_void() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // An auction that closes without bids has an expert but no winner
+    let auction_id = client.create_auction(&expert, &3_600, &3_600, &10_000, &GRACE_PERIOD, &5_000);
+    env.ledger().set_timestamp(5_000);
+    client.settle_auction(&auction_id);
+
+    let events = env.events().all();
+    let event = events
+        .iter()
+        .find(|e| {
+            let name: Result<Symbol, _> = e.1.get(0).unwrap().try_into_val(&env);
+            e.0 == client.address && name == Ok(Symbol::new(&env, "a_settle"))
+        })
+        .unwrap();
+
+    // Topics: (name, auction ID, winner, expert), with the missing winner void
+    let topic_id: u64 = event.1.get(1).unwrap().try_into_val(&env).unwrap();
+    let topic_user: Option<Address> = event.1.get(2).unwrap().try_into_val(&env).unwrap();
+    let topic_expert: Address = event.1.get(3).unwrap().try_into_val(&env).unwrap();
+    assert_eq!(topic_id, auction_id);
+    assert_eq!(topic_user, None);
+    assert_eq!(topic_expert, expert);
+
+    let data: AuctionSettledEvent = event.2.try_into_val(&env).unwrap();
+    assert_eq!(data.version, EVENT_VERSION);
+    assert_eq!(data.auction_id, auction_id);
+    assert_eq!(data.winner, None);
+    assert_eq!(data.booking_id, None);
+    assert_eq!(data.amount, 0);
+}
+
+#[test]
+fn test_expert_and_user_stats() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+    client.set_platform_fee(&1_000);
+
+    // Completed: 50 seconds at 10 per second
+    let completed =
+        client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.finalize_session(&completed, &50);
+
+    // Rejected by the expert
+    let rejected =
+        client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.reject_session(&expert, &rejected, &RejectionReason::Unavailable, &None);
+
+    // Reclaimed by the user once stale
+    let stale = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    env.ledger().set_timestamp(90_000);
+    client.reclaim_stale_session(&user, &stale);
+
+    // Gross earnings include the platform fee
+    let expert_stats = client.get_expert_stats(&expert);
+    assert_eq!(expert_stats.completed_sessions, 1);
+    assert_eq!(expert_stats.seconds_billed, 50);
+    assert_eq!(expert_stats.gross_earnings.get(token.address.clone()), Some(500));
+    assert_eq!(expert_stats.rejections, 1);
+    assert_eq!(expert_stats.reclaims, 1);
+
+    let user_stats = client.get_user_stats(&user);
+    assert_eq!(user_stats.sessions, 1);
+    assert_eq!(user_stats.total_spent.get(token.address.clone()), Some(500));
+
+    // Unknown addresses start from zero
+    let other = client.get_expert_stats(&user);
+    assert_eq!(other.completed_sessions, 0);
+    assert!(other.gross_earnings.is_empty());
+}
+
+#[test]
+fn test_expert_not_accepting_bookings() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    assert!(client.is_accepting_bookings(&expert));
+
+    let auction_id = client.create_auction(&expert, &3_600, &3_600, &10_000, &GRACE_PERIOD, &5_000);
+
+    // Expert goes on vacation
+    client.set_accepting_bookings(&expert, &false);
+    assert!(!client.is_accepting_bookings(&expert));
+
+    let result =
+        client.try_book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    assert_eq!(result, Err(Ok(VaultError::NotAcceptingBookings)));
+
+    // Questions, engagements and bids are refused too
+    let question_hash = BytesN::from_array(&env, &[1; 32]);
+    let result = client.try_ask_question(&user, &expert, &500, &question_hash, &86_400);
+    assert_eq!(result, Err(Ok(MarketError::NotAcceptingBookings)));
+    let milestones = soroban_sdk::vec![&env, 1_000_i128];
+    let result = client.try_create_engagement(&user, &expert, &milestones, &86_400);
+    assert_eq!(result, Err(Ok(MarketError::NotAcceptingBookings)));
+    let result = client.try_place_bid(&user, &auction_id, &3_600);
+    assert_eq!(result, Err(Ok(MarketError::NotAcceptingBookings)));
+    assert_eq!(token.balance(&user), 10_000);
+
+    // Back from vacation
+    client.set_accepting_bookings(&expert, &true);
+    client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    assert_eq!(token.balance(&user), 9_000);
+}
+
+#[test]
+fn test_blocked_user_cannot_book() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other_user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+    token.mint(&other_user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    client.set_user_blocked(&expert, &user, &true);
+    assert!(client.is_user_blocked(&expert, &user));
+    assert!(!client.is_user_blocked(&expert, &other_user));
+
+    let result =
+        client.try_book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    assert_eq!(result, Err(Ok(VaultError::UserBlocked)));
+
+    let question_hash = BytesN::from_array(&env, &[1; 32]);
+    let result = client.try_ask_question(&user, &expert, &500, &question_hash, &86_400);
+    assert_eq!(result, Err(Ok(MarketError::UserBlocked)));
+    let milestones = soroban_sdk::vec![&env, 1_000_i128];
+    let result = client.try_create_engagement(&user, &expert, &milestones, &86_400);
+    assert_eq!(result, Err(Ok(MarketError::UserBlocked)));
+
+    // A blocked bidder cannot take the expert's auctioned slot
+    let auction_id = client.create_auction(&expert, &3_600, &3_600, &10_000, &GRACE_PERIOD, &5_000);
+    let result = client.try_place_bid(&user, &auction_id, &3_600);
+    assert_eq!(result, Err(Ok(MarketError::UserBlocked)));
+    assert_eq!(token.balance(&user), 10_000);
+
+    // Other users and other experts are unaffected
+    client.book_session(&other_user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    let other_expert = Address::generate(&env);
+    client.book_session(&user, &other_expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    // Unblocking restores access
+    client.set_user_blocked(&expert, &user, &false);
+    client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+}
+
+#[test]
+fn test_pending_booking_caps() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let other_expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // At most 3 open bookings overall and 2 with any one expert
+    client.set_booking_limits(&3, &2, &0);
+
+    let first = client.book_session(&user, &expert, &10, &10, &0, &GRACE_PERIOD, &None);
+    let second = client.book_session(&user, &expert, &10, &10, &0, &GRACE_PERIOD, &None);
+    let result = client.try_book_session(&user, &expert, &10, &10, &0, &GRACE_PERIOD, &None);
+    assert_eq!(result, Err(Ok(VaultError::TooManyPendingBookings)));
+
+    let third = client.book_session(&user, &other_expert, &10, &10, &0, &GRACE_PERIOD, &None);
+    let result =
+        client.try_book_session(&user, &other_expert, &10, &10, &0, &GRACE_PERIOD, &None);
+    assert_eq!(result, Err(Ok(VaultError::TooManyPendingBookings)));
+    assert_eq!(client.get_open_bookings(&user), 3);
+    assert_eq!(client.get_open_pair_bookings(&user, &expert), 2);
+
+    // Each close path releases a slot
+    client.finalize_session(&first, &10);
+    client.reject_session(&expert, &second, &RejectionReason::Unavailable, &None);
+    assert_eq!(client.get_open_bookings(&user), 1);
+    assert_eq!(client.get_open_pair_bookings(&user, &expert), 0);
+
+    env.ledger().set_timestamp(90_000);
+    client.reclaim_stale_session(&user, &third);
+    assert_eq!(client.get_open_bookings(&user), 0);
+    assert_eq!(client.get_open_pair_bookings(&user, &other_expert), 0);
+
+    client.book_session(&user, &expert, &10, &10, &90_000, &GRACE_PERIOD, &None);
+    assert_eq!(client.get_open_bookings(&user), 1);
+}
+
+#[test]
+fn test_minimum_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let result = client.try_set_booking_limits(&0, &0, &-1);
+    assert_eq!(result, Err(Ok(VaultError::InvalidAmount)));
+
+    client.set_booking_limits(&0, &0, &500);
+    assert_eq!(client.get_booking_limits().min_deposit, 500);
+
+    // 10 per second for 10 seconds locks only 100
+    let result = client.try_book_session(&user, &expert, &10, &10, &0, &GRACE_PERIOD, &None);
+    assert_eq!(result, Err(Ok(VaultError::DepositBelowMinimum)));
+
+    client.book_session(&user, &expert, &10, &50, &0, &GRACE_PERIOD, &None);
+    assert_eq!(token.balance(&user), 9_500);
+}
+
+#[test]
+fn test_questions_and_engagements_count_toward_caps() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let keeper = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+    client.set_booking_limits(&2, &0, &100);
+
+    let question_hash = BytesN::from_array(&env, &[1; 32]);
+    let answer_hash = BytesN::from_array(&env, &[2; 32]);
+
+    // The minimum deposit applies to question fees
+    let result = client.try_ask_question(&user, &expert, &50, &question_hash, &86_400);
+    assert_eq!(result, Err(Ok(MarketError::DepositBelowMinimum)));
+
+    let question_id = client.ask_question(&user, &expert, &500, &question_hash, &86_400);
+    let milestones = soroban_sdk::vec![&env, 1_000_i128];
+    let engagement_id = client.create_engagement(&user, &expert, &milestones, &86_400);
+    assert_eq!(client.get_open_bookings(&user), 2);
+
+    // A capped user can open no further questions, engagements or sessions
+    let result = client.try_ask_question(&user, &expert, &500, &question_hash, &86_400);
+    assert_eq!(result, Err(Ok(MarketError::TooManyPendingBookings)));
+    let result = client.try_create_engagement(&user, &expert, &milestones, &86_400);
+    assert_eq!(result, Err(Ok(MarketError::TooManyPendingBookings)));
+    let result = client.try_book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    assert_eq!(result, Err(Ok(VaultError::TooManyPendingBookings)));
+
+    // Answering, approving the last milestone, cancelling and expiring each release a slot
+    client.answer_question(&expert, &question_id, &answer_hash);
+    client.submit_milestone(&expert, &engagement_id);
+    client.approve_milestone(&user, &engagement_id);
+    assert_eq!(client.get_open_bookings(&user), 0);
+
+    let engagement_id = client.create_engagement(&user, &expert, &milestones, &86_400);
+    let question_id = client.ask_question(&user, &expert, &500, &question_hash, &86_400);
+    client.cancel_engagement(&user, &engagement_id);
+    assert_eq!(client.get_open_bookings(&user), 1);
+
+    env.ledger().set_timestamp(86_401);
+    client.expire_question(&keeper, &question_id);
+    assert_eq!(client.get_open_bookings(&user), 0);
+    assert_eq!(client.get_open_pair_bookings(&user, &expert), 0);
+}
+
+#[test]
+fn test_reject_session_records_reason() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    let message_hash = BytesN::from_array(&env, &[7; 32]);
+    client.reject_session(
+        &expert,
+        &booking_id,
+        &RejectionReason::OutOfScope,
+        &Some(message_hash.clone()),
+    );
+
+    let booking = client.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.status, BookingStatus::Rejected);
+    assert_eq!(
+        booking.rejection_reason,
+        OptionalRejectionReason::Some(RejectionReason::OutOfScope)
+    );
+    assert_eq!(booking.rejection_hash, Some(message_hash));
+    assert_eq!(token.balance(&user), 10_000);
+}
+
+#[test]
+fn test_counter_proposal_adjusts_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // 10 per second for 100 seconds locks 1_000
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+
+    // Only the booking's expert may counter, and only the user may accept
+    let result = client.try_counter_propose(&user, &booking_id, &15, &100, &500);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+    let result = client.try_accept_counter_proposal(&user, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::ProposalNotFound)));
+
+    // Expert asks for a higher rate at a later start: 15 * 100 = 1_500
+    client.counter_propose(&expert, &booking_id, &15, &100, &500);
+    assert_eq!(client.get_counter_proposal(&booking_id).unwrap().total_deposit, 1_500);
+
+    client.accept_counter_proposal(&user, &booking_id);
+    assert_eq!(token.balance(&user), 8_500);
+    assert_eq!(token.balance(&client.address), 1_500);
+    assert!(client.get_counter_proposal(&booking_id).is_none());
+    assert_solvent(&client, &token.address);
+
+    let booking = client.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.rate_per_second, 15);
+    assert_eq!(booking.total_deposit, 1_500);
+    assert_eq!(booking.scheduled_start, 500);
+    assert_eq!(booking.expires_at, 500 + 100 + GRACE_PERIOD);
+
+    // A shorter session refunds the difference: 15 * 40 = 600
+    client.counter_propose(&expert, &booking_id, &15, &40, &500);
+    client.accept_counter_proposal(&user, &booking_id);
+    assert_eq!(token.balance(&user), 9_400);
+    assert_eq!(token.balance(&client.address), 600);
+    assert_solvent(&client, &token.address);
+
+    // Settles against the revised terms
+    env.ledger().set_timestamp(500);
+    client.finalize_session(&booking_id, &40);
+    assert_eq!(token.balance(&expert), 600);
+    assert_solvent(&client, &token.address);
+}
+
+#[test]
+fn test_counter_proposal_resets_quorum_reports() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let oracle_a = Address::generate(&env);
+    let oracle_b = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    // 2-of-2 quorum for deposits of 1000 tokens or more
+    let oracles = soroban_sdk::vec![&env, oracle_a.clone(), oracle_b.clone()];
+    client.set_oracle_quorum(&oracles, &2, &1_000);
+
+    // A report against the original terms is dropped once cheaper terms are accepted
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.submit_duration_report(&oracle_a, &booking_id, &50);
+    client.counter_propose(&expert, &booking_id, &10, &50, &500);
+    client.accept_counter_proposal(&user, &booking_id);
+    assert_eq!(client.get_duration_reports(&booking_id).len(), 0);
+    assert_solvent(&client, &token.address);
+
+    // At 500 the deposit is below the threshold, so the single oracle settles it
+    env.ledger().set_timestamp(500);
+    let result = client.try_submit_duration_report(&oracle_b, &booking_id, &50);
+    assert_eq!(result, Err(Ok(VaultError::QuorumNotRequired)));
+    client.finalize_session(&booking_id, &50);
+    assert_eq!(token.balance(&expert), 500);
+
+    // Terms that cross the threshold move settlement to the quorum
+    let booking_id = client.book_session(&user, &expert, &10, &50, &600, &GRACE_PERIOD, &None);
+    client.counter_propose(&expert, &booking_id, &20, &100, &600);
+    client.accept_counter_proposal(&user, &booking_id);
+    assert_solvent(&client, &token.address);
+
+    env.ledger().set_timestamp(600);
+    let result = client.try_finalize_session(&booking_id, &100);
+    assert_eq!(result, Err(Ok(VaultError::QuorumRequired)));
+    client.submit_duration_report(&oracle_a, &booking_id, &100);
+    client.submit_duration_report(&oracle_b, &booking_id, &100);
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Complete);
+    assert_eq!(token.balance(&expert), 2_500);
+    assert_solvent(&client, &token.address);
+}
+
+#[test]
+fn test_counter_proposal_rejected_after_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.counter_propose(&expert, &booking_id, &15, &100, &90_000);
+
+    // Once the window closes the booking can no longer be revived on new terms
+    let expires_at = client.get_booking(&booking_id).unwrap().expires_at;
+    env.ledger().set_timestamp(expires_at + 1);
+
+    let result = client.try_accept_counter_proposal(&user, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::BookingExpired)));
+    let result = client.try_counter_propose(&expert, &booking_id, &15, &100, &90_000);
+    assert_eq!(result, Err(Ok(VaultError::BookingExpired)));
+
+    assert_eq!(client.get_booking(&booking_id).unwrap().total_deposit, 1_000);
+    assert_eq!(token.balance(&user), 9_000);
+}
+
+#[test]
+fn test_reschedule_booking() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let booking_id =
+        client.book_session(&user, &expert, &10, &100, &1_000, &GRACE_PERIOD, &None);
+
+    // Outsiders cannot propose, and nothing is pending yet
+    let outsider = Address::generate(&env);
+    let result = client.try_propose_reschedule(&outsider, &booking_id, &2_000);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+    let result = client.try_accept_reschedule(&user, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::ProposalNotFound)));
+
+    // Expert proposes; the proposer cannot accept their own move
+    client.propose_reschedule(&expert, &booking_id, &5_000);
+    let result = client.try_accept_reschedule(&expert, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::NotAuthorized)));
+
+    client.accept_reschedule(&user, &booking_id);
+
+    let booking = client.get_booking(&booking_id).unwrap();
+    assert_eq!(booking.scheduled_start, 5_000);
+    assert_eq!(booking.expires_at, 5_000 + 100 + GRACE_PERIOD);
+    assert!(client.get_reschedule_proposal(&booking_id).is_none());
+
+    // User moves it again, the expert accepts; the deposit never leaves escrow
+    client.propose_reschedule(&user, &booking_id, &8_000);
+    client.accept_reschedule(&expert, &booking_id);
+    assert_eq!(token.balance(&client.address), 1_000);
+
+    let history = client.get_reschedule_history(&booking_id);
+    assert_eq!(history.len(), 2);
+    let first = history.get(0).unwrap();
+    assert_eq!(first.proposer, expert);
+    assert_eq!(first.previous_start, 1_000);
+    assert_eq!(first.new_start, 5_000);
+    assert_eq!(history.get(1).unwrap().new_start, 8_000);
+
+    // The session now settles in its new window
+    env.ledger().set_timestamp(2_000);
+    let result = client.try_finalize_session(&booking_id, &100);
+    assert_eq!(result, Err(Ok(VaultError::SessionNotStarted)));
+    env.ledger().set_timestamp(8_000);
+    client.finalize_session(&booking_id, &100);
+}
+
+#[test]
+fn test_reschedule_clears_duration_reports() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let oracle_a = Address::generate(&env);
+    let oracle_b = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let oracles = soroban_sdk::vec![&env, oracle_a.clone(), oracle_b.clone()];
+    client.set_oracle_quorum(&oracles, &2, &1_000);
+
+    // One oracle reports on the session before it is moved
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.submit_duration_report(&oracle_a, &booking_id, &50);
+
+    client.propose_reschedule(&user, &booking_id, &1_000);
+    client.accept_reschedule(&expert, &booking_id);
+    assert_eq!(client.get_duration_reports(&booking_id).len(), 0);
+
+    // The old report neither disputes nor settles the session held in its new window
+    env.ledger().set_timestamp(1_000);
+    client.submit_duration_report(&oracle_b, &booking_id, &80);
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Pending);
+
+    client.submit_duration_report(&oracle_a, &booking_id, &80);
+    assert_eq!(client.get_booking(&booking_id).unwrap().status, BookingStatus::Complete);
+    assert_eq!(token.balance(&expert), 800);
+    assert_solvent(&client, &token.address);
+}
+
+#[test]
+fn test_reschedule_into_the_past_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    env.ledger().set_timestamp(1_000);
+    let booking_id =
+        client.book_session(&user, &expert, &10, &100, &1_000, &GRACE_PERIOD, &None);
+
+    let result = client.try_propose_reschedule(&user, &booking_id, &500);
+    assert_eq!(result, Err(Ok(VaultError::InvalidSchedule)));
+
+    // A proposal that has gone stale by the time it is accepted is refused
+    client.propose_reschedule(&user, &booking_id, &2_000);
+    env.ledger().set_timestamp(3_000);
+    let result = client.try_accept_reschedule(&expert, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::InvalidSchedule)));
+}
+
+#[test]
+fn test_reschedule_rejected_after_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let expert = Address::generate(&env);
+    let oracle = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let token = create_token_contract(&env, &token_admin);
+    token.mint(&user, &10_000);
+
+    let client = create_client(&env);
+    client.init(&admin, &token.address, &oracle);
+
+    let booking_id = client.book_session(&user, &expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    client.propose_reschedule(&user, &booking_id, &90_000);
+
+    // Past its window the booking cannot be moved, even to a future start
+    let expires_at = client.get_booking(&booking_id).unwrap().expires_at;
+    env.ledger().set_timestamp(expires_at + 1);
+
+    let result = client.try_accept_reschedule(&expert, &booking_id);
+    assert_eq!(result, Err(Ok(VaultError::BookingExpired)));
+    let result = client.try_propose_reschedule(&expert, &booking_id, &90_000);
+    assert_eq!(result, Err(Ok(VaultError::BookingExpired)));
+
+    assert_eq!(client.get_booking(&booking_id).unwrap().scheduled_start, 0);
+    assert_eq!(client.get_reschedule_history(&booking_id).len(), 0);
+}
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, BytesN, Map, Vec};
 
 /// Status of a booking in the payment vault
 #[contracttype]
@@ -9,6 +9,154 @@ pub enum BookingStatus {
     Complete = 1,
     Rejected = 2,
     Reclaimed = 3,
+    Disputed = 4,
+    Expired = 5,
+}
+
+/// Reason code an expert gives when rejecting a booking
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum RejectionReason {
+    Unavailable = 0,
+    OutOfScope = 1,
+    Price = 2,
+    Other = 3,
+}
+
+/// Option<RejectionReason> for contract types, which cannot hold an Option of an enum
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OptionalRejectionReason {
+    None,
+    Some(RejectionReason),
+}
+
+/// How a session duration is rounded to the billing increment
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum RoundingMode {
+    Down = 0,
+    Up = 1,
+    Nearest = 2,
+}
+
+/// Billing rules applied to the actual duration before calculating the payout
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BillingPolicy {
+    pub increment: u64,             // Billing increment in seconds (1 = per-second)
+    pub min_duration: u64,          // Minimum billable seconds for a session that took place
+    pub rounding: RoundingMode,     // Rounding applied to the increment
+}
+
+/// Rate applied from `threshold` seconds into the session until the next tier starts
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PricingTier {
+    pub threshold: u64,             // Seconds into the session where this rate begins
+    pub rate_per_second: i128,      // Payment rate per second within this tier
+}
+
+/// Preview of how a booking would settle for a given duration
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SettlementQuote {
+    pub billable_duration: u64,     // Duration after rounding and minimum charge
+    pub expert_pay: i128,           // Amount paid to the expert, net of the platform fee
+    pub platform_fee: i128,         // Amount retained in the platform fee pool
+    pub refund: i128,               // Amount refunded to the user
+}
+
+/// Discount granted by a voucher campaign
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VoucherDiscount {
+    Bps(u32),                       // Percentage of the deposit, in basis points
+    Fixed(i128),                    // Fixed amount, capped at the deposit
+}
+
+/// Platform-funded campaign that subsidizes bookings
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoucherCampaign {
+    pub id: u64,                    // Storage key identifier
+    pub discount: VoucherDiscount,  // Discount applied to each redemption
+    pub budget: i128,               // Remaining budget held by the vault
+    pub per_user_limit: u32,        // Maximum redemptions per user
+    pub expires_at: u64,            // Ledger timestamp after which vouchers cannot be redeemed
+    pub active: bool,               // False once the admin closes the campaign
+    pub created_at: u64,            // Ledger timestamp when campaign was created
+}
+
+/// Where a booking's deposit came from, beyond the user's own tokens
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BookingFunding {
+    pub voucher_id: Option<u64>,    // Voucher campaign that subsidized the booking
+    pub subsidy: i128,              // Part of the deposit paid by the campaign
+    pub pool_id: Option<u64>,       // Budget pool that paid the deposit; refunds return to it
+}
+
+/// Employer or grant budget that beneficiaries spend on sessions with allowlisted experts
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BudgetPool {
+    pub id: u64,                    // Storage key identifier
+    pub owner: Address,             // Funds the pool and manages beneficiaries and experts
+    pub balance: i128,              // Unspent funds held by the vault
+    pub period: u64,                // Length of a spending-limit period in seconds
+    pub created_at: u64,            // Ledger timestamp when pool was created; periods align to it
+}
+
+/// A beneficiary's spending limit and usage in the current period
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PoolBeneficiary {
+    pub limit_per_period: i128,     // Maximum spend per period
+    pub spent: i128,                // Spent in the period starting at period_start
+    pub period_start: u64,          // Ledger timestamp when the tracked period began
+}
+
+/// What the vault owes in one token compared with what it holds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SolvencyReport {
+    pub token: Address,             // Token the report covers
+    pub balance: i128,              // Vault's token balance
+    pub locked: i128,               // Escrowed deposits, retainers, voucher budgets and pools
+    pub fees: i128,                 // Platform fee pool
+    pub earnings: i128,             // Expert pay accrued but not yet withdrawn
+    pub surplus: i128,              // balance - (locked + fees + earnings); negative if insolvent
+}
+
+/// Anti-spam limits on new bookings; zero disables a limit
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BookingLimits {
+    pub max_pending_per_user: u32,  // Open bookings a user may hold across all experts
+    pub max_pending_per_pair: u32,  // Open bookings a user may hold with one expert
+    pub min_deposit: i128,          // Smallest total deposit a booking may lock
+}
+
+/// Running totals for an expert, updated as their bookings close
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpertStats {
+    pub completed_sessions: u32,    // Bookings settled or streamed to completion
+    pub seconds_billed: u64,        // Billable seconds across completed sessions
+    pub gross_earnings: Map<Address, i128>, // Token -> expert pay plus platform fee
+    pub rejections: u32,            // Bookings the expert rejected
+    pub reclaims: u32,              // Stale bookings refunded to the user
+}
+
+/// Running totals for a user, updated as their bookings complete
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserStats {
+    pub sessions: u32,              // Bookings settled or streamed to completion
+    pub total_spent: Map<Address, i128>, // Token -> amount charged for completed sessions
 }
 
 /// Record of a consultation booking with deposit locked
@@ -18,9 +166,179 @@ pub struct BookingRecord {
     pub id: u64,                    // Storage key identifier
     pub user: Address,              // User who created the booking
     pub expert: Address,            // Expert providing consultation
-    pub rate_per_second: i128,      // Payment rate per second
+    pub rate_per_second: i128,      // Payment rate per second of the first pricing tier
+    pub pricing: Vec<PricingTier>,  // Pricing schedule, ordered by threshold
     pub max_duration: u64,          // Maximum booked duration in seconds
-    pub total_deposit: i128,        // Total deposit (rate_per_second * max_duration)
+    pub total_deposit: i128,        // Total deposit (pricing schedule over max_duration)
     pub status: BookingStatus,      // Current booking status
     pub created_at: u64,            // Ledger timestamp when booking was created
+    pub scheduled_start: u64,       // Ledger timestamp when the session is meant to start
+    pub expires_at: u64,            // End of the finalization window (start + max_duration + grace)
+    pub billing: BillingPolicy,     // Expert's billing policy at booking time
+    pub funding: BookingFunding,    // Subsidies included in total_deposit
+    pub total_tips: i128,           // Tips paid to the expert after completion
+    pub stream: bool,               // Settles by ledger time instead of through the oracle
+    pub withdrawn: i128,            // Streamed pay already paid out to the expert (gross)
+    pub stopped_at: Option<u64>,    // Ledger timestamp when the user stopped the stream
+    pub rejection_reason: OptionalRejectionReason, // Reason given by the expert on rejection
+    pub rejection_hash: Option<BytesN<32>>, // Hash of the expert's off-chain rejection message
+}
+
+/// Revised terms an expert offers for a pending booking, applied once the user accepts
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CounterProposal {
+    pub rate_per_second: i128,      // Proposed flat rate per second
+    pub max_duration: u64,          // Proposed maximum duration in seconds
+    pub scheduled_start: u64,       // Proposed start time
+    pub total_deposit: i128,        // Deposit the revised terms lock
+    pub proposed_at: u64,           // Ledger timestamp when the expert proposed
+}
+
+/// New start time one party of a pending booking proposes, awaiting the other's acceptance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RescheduleProposal {
+    pub proposer: Address,          // User or expert who proposed the move
+    pub new_start: u64,             // Proposed start time
+    pub proposed_at: u64,           // Ledger timestamp when proposed
+}
+
+/// An accepted reschedule of a booking
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RescheduleRecord {
+    pub proposer: Address,          // Party who proposed the move
+    pub previous_start: u64,        // Start time before the move
+    pub new_start: u64,             // Start time after the move
+    pub accepted_at: u64,           // Ledger timestamp when the other party accepted
+}
+
+/// A duration reported by one member of the oracle set for a high-value booking
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DurationReport {
+    pub oracle: Address,            // Oracle that submitted the report
+    pub actual_duration: u64,       // Reported session duration in seconds
+}
+
+/// M-of-N oracle configuration for finalizing high-value bookings
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleQuorum {
+    pub oracles: Vec<Address>,      // Distinct oracles allowed to report
+    pub threshold: u32,             // Matching reports required to settle
+    pub min_deposit: i128,          // Deposits at or above this need a quorum
+}
+
+/// Fixed-price engagement paid out milestone by milestone
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct EngagementRecord {
+    pub id: u64,                    // Storage key identifier, shared with bookings
+    pub user: Address,              // User who funded the engagement
+    pub expert: Address,            // Expert delivering the milestones
+    pub milestones: Vec<i128>,      // Ordered milestone amounts
+    pub next_milestone: u32,        // Index of the milestone currently in progress
+    pub submitted_at: Option<u64>,  // When the current milestone was submitted for approval
+    pub approval_timeout: u64,      // Seconds after submission before auto-approval is allowed
+    pub total_deposit: i128,        // Sum of all milestone amounts
+    pub released: i128,             // Amount already released for approved milestones
+    pub status: BookingStatus,      // Current engagement status
+    pub created_at: u64,            // Ledger timestamp when engagement was created
+}
+
+/// Fixed-fee written question that the expert must answer before the deadline
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct QuestionRecord {
+    pub id: u64,                    // Storage key identifier, shared with bookings
+    pub user: Address,              // User who asked the question
+    pub expert: Address,            // Expert asked to answer
+    pub fee: i128,                  // Fee held in escrow until answered or expired
+    pub question_hash: BytesN<32>,  // Hash of the off-chain question content
+    pub answer_hash: Option<BytesN<32>>, // Hash of the off-chain answer, once submitted
+    pub deadline: u64,              // Ledger timestamp by which the answer is due
+    pub status: BookingStatus,      // Current question status
+    pub created_at: u64,            // Ledger timestamp when question was asked
+}
+
+/// Open bounty that any verified expert can answer
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct BountyRecord {
+    pub id: u64,                    // Storage key identifier
+    pub poster: Address,            // User who posted and funded the bounty
+    pub amount: i128,               // Bounty amount held in escrow
+    pub question_hash: BytesN<32>,  // Hash of the off-chain question content
+    pub expires_at: u64,            // Ledger timestamp after which no answers are accepted
+    pub submission_count: u32,      // Number of answers submitted
+    pub status: BookingStatus,      // Current bounty status
+    pub created_at: u64,            // Ledger timestamp when bounty was posted
+}
+
+/// Answer submitted to a bounty by a verified expert
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BountySubmission {
+    pub expert: Address,            // Expert who submitted the answer
+    pub content_hash: BytesN<32>,   // Hash of the off-chain answer content
+    pub submitted_at: u64,          // Ledger timestamp of the submission
+}
+
+/// Share of a bounty awarded to one submission
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BountyAward {
+    pub submission: u32,            // Index of the winning submission
+    pub amount: i128,               // Amount awarded to its expert
+}
+
+/// Auctioned expert time slot; the winning bid becomes a funded booking
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AuctionRecord {
+    pub id: u64,                    // Storage key identifier
+    pub expert: Address,            // Expert offering the slot
+    pub reserve_price: i128,        // Minimum accepted bid
+    pub max_duration: u64,          // Slot length in seconds
+    pub scheduled_start: u64,       // Ledger timestamp when the slot starts
+    pub grace_period: u64,          // Grace period of the resulting booking
+    pub end_time: u64,              // Ledger timestamp when bidding closes
+    pub highest_bidder: Option<Address>, // Current highest bidder, if any
+    pub highest_bid: i128,          // Current highest bid held in escrow
+    pub booking_id: Option<u64>,    // Booking created for the winner on settlement
+    pub status: BookingStatus,      // Pending while open, Complete or Expired once settled
+    pub created_at: u64,            // Ledger timestamp when auction was created
+}
+
+/// Recurring retainer: each period charges a fixed price for an allotment of seconds
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct RetainerRecord {
+    pub id: u64,                    // Storage key identifier
+    pub user: Address,              // Client paying through a token allowance
+    pub expert: Address,            // Expert on retainer
+    pub price_per_period: i128,     // Amount charged each period
+    pub period: u64,                // Period length in seconds
+    pub allotment: u64,             // Seconds of expert time granted each period
+    pub rollover: bool,             // Whether unused seconds carry into the next period
+    pub available_seconds: u64,     // Seconds left to use in the current period
+    pub escrow: i128,               // Held for available_seconds; paid out as time is used
+    pub period_end: u64,            // Ledger timestamp when the next charge is due
+    pub cancel_at_period_end: bool, // Set by either side; the retainer ends at period_end
+    pub status: BookingStatus,      // Pending while running, Complete once ended
+    pub created_at: u64,            // Ledger timestamp when retainer was created
+}
+
+/// Off-chain price quote signed by the expert's registered ed25519 key
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Quote {
+    pub expert: Address,            // Expert offering the quote
+    pub user: Address,              // Only this user may redeem the quote
+    pub rate_per_second: i128,      // Agreed payment rate per second
+    pub max_duration: u64,          // Agreed maximum duration in seconds
+    pub expires_at: u64,            // Ledger timestamp after which the quote cannot be redeemed
+    pub nonce: u64,                 // Per-expert nonce; each quote can be redeemed once
 }
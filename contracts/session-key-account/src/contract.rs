@@ -0,0 +1,193 @@
+use crate::error::AccountError;
+use crate::events;
+use crate::storage;
+use crate::types::{AccountSignature, SessionPolicy, SessionState};
+use soroban_sdk::auth::{Context, ContractContext};
+use soroban_sdk::crypto::Hash;
+use soroban_sdk::{symbol_short, Address, BytesN, Env, TryFromVal, Vec};
+
+pub fn initialize_account(
+    env: &Env,
+    owner_key: &BytesN<32>,
+    vault: &Address,
+    token: &Address,
+) -> Result<(), AccountError> {
+    if storage::has_owner_key(env) {
+        return Err(AccountError::AlreadyInitialized);
+    }
+
+    storage::set_owner_key(env, owner_key);
+    storage::set_vault(env, vault);
+    storage::set_token(env, token);
+
+    Ok(())
+}
+
+pub fn add_session_key(
+    env: &Env,
+    session_key: &BytesN<32>,
+    policy: SessionPolicy,
+) -> Result<(), AccountError> {
+    // 1. Only the account itself (signed by the owner key) manages session keys
+    let owner_key = storage::get_owner_key(env).ok_or(AccountError::NotInitialized)?;
+    env.current_contract_address().require_auth();
+
+    // 2. Validate the policy
+    if *session_key == owner_key
+        || policy.functions.is_empty()
+        || policy.spend_cap < 0
+        || policy.expires_at <= env.ledger().timestamp()
+    {
+        return Err(AccountError::InvalidPolicy);
+    }
+
+    // 3. Register the key with nothing spent
+    let booking_id = policy.booking_id;
+    let expires_at = policy.expires_at;
+    storage::save_session(env, session_key, &SessionState { policy, spent: 0 });
+
+    events::session_key_added(env, session_key, booking_id, expires_at);
+
+    Ok(())
+}
+
+pub fn revoke_session_key(env: &Env, session_key: &BytesN<32>) -> Result<(), AccountError> {
+    // 1. Only the account itself (signed by the owner key) manages session keys
+    storage::get_owner_key(env).ok_or(AccountError::NotInitialized)?;
+    env.current_contract_address().require_auth();
+
+    // 2. Remove the key
+    storage::get_session(env, session_key).ok_or(AccountError::SessionKeyNotFound)?;
+    storage::remove_session(env, session_key);
+
+    events::session_key_revoked(env, session_key);
+
+    Ok(())
+}
+
+pub fn check_auth(
+    env: &Env,
+    signature_payload: &Hash<32>,
+    signature: &AccountSignature,
+    auth_contexts: &Vec<Context>,
+) -> Result<(), AccountError> {
+    // 1. Verify the signature; a bad signature aborts the invocation
+    env.crypto().ed25519_verify(
+        &signature.public_key,
+        &signature_payload.clone().into(),
+        &signature.signature,
+    );
+
+    // 2. The owner key may authorize anything
+    let owner_key = storage::get_owner_key(env).ok_or(AccountError::NotInitialized)?;
+    if signature.public_key == owner_key {
+        return Ok(());
+    }
+
+    // 3. A session key must be registered and unexpired
+    let mut session = storage::get_session(env, &signature.public_key)
+        .ok_or(AccountError::SessionKeyNotFound)?;
+
+    if env.ledger().timestamp() > session.policy.expires_at {
+        return Err(AccountError::SessionExpired);
+    }
+
+    // 4. Every authorized call must fit the policy
+    let vault = storage::get_vault(env);
+    let token = storage::get_token(env);
+    let mut spent = session.spent;
+    let mut vault_called = false;
+    let mut transferred = false;
+
+    for context in auth_contexts.iter() {
+        let call = match context {
+            Context::Contract(call) => call,
+            _ => return Err(AccountError::FunctionNotAllowed),
+        };
+
+        if call.contract == vault {
+            check_vault_call(env, &session.policy, &call)?;
+            vault_called = true;
+        } else if call.contract == token {
+            spent = spent
+                .checked_add(transfer_amount(env, &call, &vault)?)
+                .ok_or(AccountError::SpendCapExceeded)?;
+            transferred = true;
+        } else {
+            return Err(AccountError::FunctionNotAllowed);
+        }
+    }
+
+    // Transfers are only signed as part of an allowed vault call for the booking
+    if transferred && !vault_called {
+        return Err(AccountError::FunctionNotAllowed);
+    }
+
+    if spent > session.policy.spend_cap {
+        return Err(AccountError::SpendCapExceeded);
+    }
+
+    // 5. Record the spend against the cap
+    if spent != session.spent {
+        session.spent = spent;
+        storage::save_session(env, &signature.public_key, &session);
+    }
+
+    Ok(())
+}
+
+/// Vault calls must be allowlisted and target the session's booking (second argument)
+fn check_vault_call(
+    env: &Env,
+    policy: &SessionPolicy,
+    call: &ContractContext,
+) -> Result<(), AccountError> {
+    if !policy.functions.contains(&call.fn_name) {
+        return Err(AccountError::FunctionNotAllowed);
+    }
+
+    let booking_id = call
+        .args
+        .get(1)
+        .and_then(|arg| u64::try_from_val(env, &arg).ok())
+        .ok_or(AccountError::BookingNotAllowed)?;
+
+    if booking_id != policy.booking_id {
+        return Err(AccountError::BookingNotAllowed);
+    }
+
+    Ok(())
+}
+
+/// Token calls are limited to transfer(from, vault, amount); returns the amount
+fn transfer_amount(
+    env: &Env,
+    call: &ContractContext,
+    vault: &Address,
+) -> Result<i128, AccountError> {
+    if call.fn_name != symbol_short!("transfer") {
+        return Err(AccountError::FunctionNotAllowed);
+    }
+
+    let to = call
+        .args
+        .get(1)
+        .and_then(|arg| Address::try_from_val(env, &arg).ok())
+        .ok_or(AccountError::FunctionNotAllowed)?;
+
+    if to != *vault {
+        return Err(AccountError::FunctionNotAllowed);
+    }
+
+    let amount = call
+        .args
+        .get(2)
+        .and_then(|arg| i128::try_from_val(env, &arg).ok())
+        .ok_or(AccountError::FunctionNotAllowed)?;
+
+    if amount < 0 {
+        return Err(AccountError::FunctionNotAllowed);
+    }
+
+    Ok(amount)
+}
@@ -0,0 +1,15 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum AccountError {
+    NotInitialized = 1,
+    AlreadyInitialized = 2,
+    SessionKeyNotFound = 3,
+    SessionExpired = 4,
+    FunctionNotAllowed = 5,
+    BookingNotAllowed = 6,
+    SpendCapExceeded = 7,
+    InvalidPolicy = 8,
+}
@@ -0,0 +1,15 @@
+use soroban_sdk::{symbol_short, BytesN, Env};
+
+/// Emitted when the owner registers a session key
+#[allow(deprecated)]
+pub fn session_key_added(env: &Env, session_key: &BytesN<32>, booking_id: u64, expires_at: u64) {
+    let topics = (symbol_short!("sk_add"), session_key.clone());
+    env.events().publish(topics, (booking_id, expires_at));
+}
+
+/// Emitted when the owner revokes a session key
+#[allow(deprecated)]
+pub fn session_key_revoked(env: &Env, session_key: &BytesN<32>) {
+    let topics = (symbol_short!("sk_revoke"), session_key.clone());
+    env.events().publish(topics, ());
+}
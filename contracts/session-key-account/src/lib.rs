@@ -0,0 +1,68 @@
+#![no_std]
+
+mod contract;
+mod error;
+mod events;
+mod storage;
+#[cfg(test)]
+mod test;
+mod types;
+
+use crate::error::AccountError;
+use crate::types::{AccountSignature, SessionPolicy, SessionState};
+use soroban_sdk::auth::{Context, CustomAccountInterface};
+use soroban_sdk::crypto::Hash;
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, Vec};
+
+#[contract]
+pub struct SessionKeyAccount;
+
+#[contractimpl]
+impl SessionKeyAccount {
+    /// Initialize the account with the owner's ed25519 key
+    /// and the vault and token that session keys may act on
+    pub fn init(
+        env: Env,
+        owner_key: BytesN<32>,
+        vault: Address,
+        token: Address,
+    ) -> Result<(), AccountError> {
+        contract::initialize_account(&env, &owner_key, &vault, &token)
+    }
+
+    /// Register an ephemeral session key limited by a policy (Owner-only)
+    pub fn add_session_key(
+        env: Env,
+        session_key: BytesN<32>,
+        policy: SessionPolicy,
+    ) -> Result<(), AccountError> {
+        contract::add_session_key(&env, &session_key, policy)
+    }
+
+    /// Revoke a session key before it expires (Owner-only)
+    pub fn revoke_session_key(env: Env, session_key: BytesN<32>) -> Result<(), AccountError> {
+        contract::revoke_session_key(&env, &session_key)
+    }
+
+    /// Get a session key's policy and spend so far (read-only)
+    pub fn get_session(env: Env, session_key: BytesN<32>) -> Option<SessionState> {
+        storage::get_session(&env, &session_key)
+    }
+}
+
+#[contractimpl]
+impl CustomAccountInterface for SessionKeyAccount {
+    type Signature = AccountSignature;
+    type Error = AccountError;
+
+    /// Accepts the owner key for any call, and session keys within their policy
+    #[allow(non_snake_case)]
+    fn __check_auth(
+        env: Env,
+        signature_payload: Hash<32>,
+        signature: AccountSignature,
+        auth_contexts: Vec<Context>,
+    ) -> Result<(), AccountError> {
+        contract::check_auth(&env, &signature_payload, &signature, &auth_contexts)
+    }
+}
@@ -0,0 +1,59 @@
+use crate::types::SessionState;
+use soroban_sdk::{contracttype, Address, BytesN, Env};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    OwnerKey,
+    Vault,
+    Token,
+    Session(BytesN<32>), // Session public key -> SessionState
+}
+
+// --- Configuration ---
+pub fn has_owner_key(env: &Env) -> bool {
+    env.storage().instance().has(&DataKey::OwnerKey)
+}
+
+pub fn set_owner_key(env: &Env, owner_key: &BytesN<32>) {
+    env.storage().instance().set(&DataKey::OwnerKey, owner_key);
+}
+
+pub fn get_owner_key(env: &Env) -> Option<BytesN<32>> {
+    env.storage().instance().get(&DataKey::OwnerKey)
+}
+
+pub fn set_vault(env: &Env, vault: &Address) {
+    env.storage().instance().set(&DataKey::Vault, vault);
+}
+
+pub fn get_vault(env: &Env) -> Address {
+    env.storage().instance().get(&DataKey::Vault).unwrap()
+}
+
+pub fn set_token(env: &Env, token: &Address) {
+    env.storage().instance().set(&DataKey::Token, token);
+}
+
+pub fn get_token(env: &Env) -> Address {
+    env.storage().instance().get(&DataKey::Token).unwrap()
+}
+
+// --- Session Keys ---
+pub fn save_session(env: &Env, session_key: &BytesN<32>, session: &SessionState) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Session(session_key.clone()), session);
+}
+
+pub fn get_session(env: &Env, session_key: &BytesN<32>) -> Option<SessionState> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Session(session_key.clone()))
+}
+
+pub fn remove_session(env: &Env, session_key: &BytesN<32>) {
+    env.storage()
+        .persistent()
+        .remove(&DataKey::Session(session_key.clone()));
+}
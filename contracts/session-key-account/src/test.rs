@@ -0,0 +1,365 @@
+#![cfg(test)]
+
+extern crate std;
+
+use crate::error::AccountError;
+use crate::types::{AccountSignature, SessionPolicy};
+use crate::{SessionKeyAccount, SessionKeyAccountClient};
+use ed25519_dalek::{Signer, SigningKey};
+use payment_vault_contract::{PaymentVaultContract, PaymentVaultContractClient};
+use soroban_sdk::xdr::{
+    Hash, HashIdPreimage, HashIdPreimageSorobanAuthorization, InvokeContractArgs, Limits,
+    ScAddress, ScVal, SorobanAddressCredentials, SorobanAuthorizationEntry,
+    SorobanAuthorizedFunction, SorobanAuthorizedInvocation, SorobanCredentials, WriteXdr,
+};
+use soroban_sdk::auth::{Context, ContractContext};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token, vec, Address, Bytes, BytesN, Env, IntoVal, Symbol, TryFromVal, Val,
+};
+
+const GRACE_PERIOD: u64 = 3_600;
+
+struct Setup<'a> {
+    env: Env,
+    account: SessionKeyAccountClient<'a>,
+    vault: PaymentVaultContractClient<'a>,
+    token: token::StellarAssetClient<'a>,
+    owner: SigningKey,
+    session: SigningKey,
+    expert: Address,
+}
+
+/// Vault, token and an account funded with 10_000, all set up with mocked auth
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let oracle = Address::generate(&env);
+    let expert = Address::generate(&env);
+
+    let token_admin = Address::generate(&env);
+    let sac = env.register_stellar_asset_contract_v2(token_admin);
+    let token = token::StellarAssetClient::new(&env, &sac.address());
+
+    let vault = PaymentVaultContractClient::new(&env, &env.register(PaymentVaultContract, ()));
+    vault.init(&admin, &token.address, &oracle);
+
+    let owner = SigningKey::from_bytes(&[1; 32]);
+    let session = SigningKey::from_bytes(&[2; 32]);
+
+    let account = SessionKeyAccountClient::new(&env, &env.register(SessionKeyAccount, ()));
+    account.init(&public_key(&env, &owner), &vault.address, &token.address);
+    token.mint(&account.address, &10_000);
+
+    Setup {
+        env,
+        account,
+        vault,
+        token,
+        owner,
+        session,
+        expert,
+    }
+}
+
+fn public_key(env: &Env, signer: &SigningKey) -> BytesN<32> {
+    BytesN::from_array(env, &signer.verifying_key().to_bytes())
+}
+
+fn policy(env: &Env, functions: &[&str], booking_id: u64, spend_cap: i128) -> SessionPolicy {
+    let mut names = vec![env];
+    for name in functions {
+        names.push_back(Symbol::new(env, name));
+    }
+    SessionPolicy {
+        functions: names,
+        booking_id,
+        spend_cap,
+        expires_at: 1_000,
+    }
+}
+
+fn invocation(
+    env: &Env,
+    contract: &Address,
+    fn_name: &str,
+    args: soroban_sdk::Vec<Val>,
+    sub_invocations: std::vec::Vec<SorobanAuthorizedInvocation>,
+) -> SorobanAuthorizedInvocation {
+    let args: std::vec::Vec<ScVal> = args
+        .iter()
+        .map(|arg| ScVal::try_from_val(env, &arg).unwrap())
+        .collect();
+
+    SorobanAuthorizedInvocation {
+        function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+            contract_address: ScAddress::from(contract),
+            function_name: fn_name.try_into().unwrap(),
+            args: args.try_into().unwrap(),
+        }),
+        sub_invocations: sub_invocations.try_into().unwrap(),
+    }
+}
+
+/// Signs an authorization entry for the account the way a wallet would
+fn sign(
+    env: &Env,
+    account: &Address,
+    signer: &SigningKey,
+    nonce: i64,
+    root_invocation: SorobanAuthorizedInvocation,
+) -> SorobanAuthorizationEntry {
+    let signature_expiration_ledger = env.ledger().sequence() + 100;
+    let preimage = HashIdPreimage::SorobanAuthorization(HashIdPreimageSorobanAuthorization {
+        network_id: Hash(env.ledger().network_id().to_array()),
+        nonce,
+        signature_expiration_ledger,
+        invocation: root_invocation.clone(),
+    });
+    let preimage = preimage.to_xdr(Limits::none()).unwrap();
+    let payload = env.crypto().sha256(&Bytes::from_slice(env, &preimage));
+
+    let signature = AccountSignature {
+        public_key: public_key(env, signer),
+        signature: BytesN::from_array(env, &signer.sign(&payload.to_array()).to_bytes()),
+    };
+    let signature_val: Val = signature.into_val(env);
+
+    SorobanAuthorizationEntry {
+        credentials: SorobanCredentials::Address(SorobanAddressCredentials {
+            address: ScAddress::from(account),
+            nonce,
+            signature_expiration_ledger,
+            signature: ScVal::try_from_val(env, &signature_val).unwrap(),
+        }),
+        root_invocation,
+    }
+}
+
+/// tip(user, booking_id, amount) with its nested token transfer to the vault
+fn tip_invocation(s: &Setup, booking_id: u64, amount: i128) -> SorobanAuthorizedInvocation {
+    let env = &s.env;
+    let transfer = invocation(
+        env,
+        &s.token.address,
+        "transfer",
+        (s.account.address.clone(), s.vault.address.clone(), amount).into_val(env),
+        std::vec![],
+    );
+    invocation(
+        env,
+        &s.vault.address,
+        "tip",
+        (s.account.address.clone(), booking_id, amount).into_val(env),
+        std::vec![transfer],
+    )
+}
+
+#[test]
+fn test_session_key_tips_within_cap() {
+    let s = setup();
+    let env = &s.env;
+
+    let booking_id = s
+        .vault
+        .book_session(&s.account.address, &s.expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    s.vault.finalize_session(&booking_id, &100);
+    let other_id = s
+        .vault
+        .book_session(&s.account.address, &s.expert, &10, &100, &0, &GRACE_PERIOD, &None);
+    s.vault.finalize_session(&other_id, &100);
+    assert_eq!(s.token.balance(&s.expert), 2_000);
+
+    let session_key = public_key(env, &s.session);
+    s.account
+        .add_session_key(&session_key, &policy(env, &["tip"], booking_id, 100));
+
+    let session_tip = |nonce: i64, booking_id: u64, amount: i128| {
+        sign(env, &s.account.address, &s.session, nonce, tip_invocation(&s, booking_id, amount))
+    };
+
+    // The session key signs a tip without the owner
+    env.set_auths(&[session_tip(1, booking_id, 60)]);
+    s.vault.tip(&s.account.address, &booking_id, &60);
+    assert_eq!(s.token.balance(&s.expert), 2_060);
+    assert_eq!(s.account.get_session(&session_key).unwrap().spent, 60);
+
+    // A second tip would take the key past its spend cap
+    env.set_auths(&[session_tip(2, booking_id, 50)]);
+    assert!(s.vault.try_tip(&s.account.address, &booking_id, &50).is_err());
+
+    // The key is bound to one booking
+    env.set_auths(&[session_tip(3, other_id, 10)]);
+    assert!(s.vault.try_tip(&s.account.address, &other_id, &10).is_err());
+
+    // And expires with its policy
+    env.ledger().set_timestamp(1_001);
+    env.set_auths(&[session_tip(4, booking_id, 10)]);
+    assert!(s.vault.try_tip(&s.account.address, &booking_id, &10).is_err());
+
+    assert_eq!(s.token.balance(&s.expert), 2_060);
+    assert_eq!(s.account.get_session(&session_key).unwrap().spent, 60);
+
+    // The owner key is not limited by any session policy
+    env.set_auths(&[sign(env, &s.account.address, &s.owner, 5, tip_invocation(&s, other_id, 500))]);
+    s.vault.tip(&s.account.address, &other_id, &500);
+    assert_eq!(s.token.balance(&s.expert), 2_560);
+}
+
+#[test]
+fn test_session_key_stops_stream() {
+    let s = setup();
+    let env = &s.env;
+
+    let booking_id = s.vault.book_stream(&s.account.address, &s.expert, &10, &100, &0);
+    assert_eq!(s.token.balance(&s.account.address), 9_000);
+
+    let session_key = public_key(env, &s.session);
+    s.account
+        .add_session_key(&session_key, &policy(env, &["stop_stream"], booking_id, 0));
+
+    let stop = |nonce: i64| {
+        let root = invocation(
+            env,
+            &s.vault.address,
+            "stop_stream",
+            (s.account.address.clone(), booking_id).into_val(env),
+            std::vec![],
+        );
+        sign(env, &s.account.address, &s.session, nonce, root)
+    };
+
+    let session_tip = |nonce: i64, booking_id: u64, amount: i128| {
+        sign(env, &s.account.address, &s.session, nonce, tip_invocation(&s, booking_id, amount))
+    };
+
+    // Functions outside the policy are refused even for the right booking
+    env.set_auths(&[session_tip(1, booking_id, 0)]);
+    assert!(s.vault.try_tip(&s.account.address, &booking_id, &0).is_err());
+
+    // Stop the stream after 40 seconds; 600 comes straight back to the account
+    env.ledger().set_timestamp(40);
+    env.set_auths(&[stop(2)]);
+    s.vault.stop_stream(&s.account.address, &booking_id);
+    assert_eq!(s.token.balance(&s.account.address), 9_600);
+
+    // Once revoked by the owner, the key signs nothing
+    let revoke = invocation(
+        env,
+        &s.account.address,
+        "revoke_session_key",
+        (session_key.clone(),).into_val(env),
+        std::vec![],
+    );
+    env.set_auths(&[sign(env, &s.account.address, &s.owner, 3, revoke)]);
+    s.account.revoke_session_key(&session_key);
+    assert!(s.account.get_session(&session_key).is_none());
+
+    env.set_auths(&[stop(4)]);
+    assert!(s.vault.try_stop_stream(&s.account.address, &booking_id).is_err());
+}
+
+#[test]
+fn test_session_policy_validation() {
+    let s = setup();
+    let env = &s.env;
+
+    let res = s.account.try_init(&public_key(env, &s.owner), &s.vault.address, &s.token.address);
+    assert!(res.is_err());
+
+    // The owner key cannot double as a session key, and a policy needs functions
+    let owner_key = public_key(env, &s.owner);
+    let res = s.account.try_add_session_key(&owner_key, &policy(env, &["tip"], 1, 100));
+    assert_eq!(res, Err(Ok(AccountError::InvalidPolicy)));
+
+    let session_key = public_key(env, &s.session);
+    let res = s.account.try_add_session_key(&session_key, &policy(env, &[], 1, 100));
+    assert_eq!(res, Err(Ok(AccountError::InvalidPolicy)));
+
+    env.ledger().set_timestamp(1_000);
+    let res = s.account.try_add_session_key(&session_key, &policy(env, &["tip"], 1, 100));
+    assert_eq!(res, Err(Ok(AccountError::InvalidPolicy)));
+}
+
+#[test]
+fn test_check_auth_policy_errors() {
+    let s = setup();
+    let env = &s.env;
+
+    let session_key = public_key(env, &s.session);
+    s.account
+        .add_session_key(&session_key, &policy(env, &["tip"], 7, 100));
+
+    let payload = BytesN::from_array(env, &[9; 32]);
+    let signature: Val = AccountSignature {
+        public_key: session_key.clone(),
+        signature: BytesN::from_array(env, &s.session.sign(&[9; 32]).to_bytes()),
+    }
+    .into_val(env);
+
+    let call = |contract: &Address, fn_name: &str, args: soroban_sdk::Vec<Val>| {
+        Context::Contract(ContractContext {
+            contract: contract.clone(),
+            fn_name: Symbol::new(env, fn_name),
+            args,
+        })
+    };
+    let check = |contexts: soroban_sdk::Vec<Context>| {
+        env.try_invoke_contract_check_auth::<AccountError>(
+            &s.account.address,
+            &payload,
+            signature,
+            &contexts,
+        )
+    };
+
+    let tip_args = (s.account.address.clone(), 7_u64, 30_i128).into_val(env);
+    let tip = call(&s.vault.address, "tip", tip_args);
+    let transfer_to = |to: &Address, amount: i128| {
+        call(
+            &s.token.address,
+            "transfer",
+            (s.account.address.clone(), to.clone(), amount).into_val(env),
+        )
+    };
+    let transfer = |amount: i128| transfer_to(&s.vault.address, amount);
+
+    assert_eq!(check(vec![env, tip.clone(), transfer(30)]), Ok(()));
+    assert_eq!(
+        check(vec![env, tip.clone(), transfer(71)]),
+        Err(Ok(AccountError::SpendCapExceeded))
+    );
+
+    // Transfers need an allowed vault call alongside, and may only pay the vault
+    assert_eq!(check(vec![env, transfer(30)]), Err(Ok(AccountError::FunctionNotAllowed)));
+    assert_eq!(
+        check(vec![env, tip.clone(), transfer_to(&s.expert, 30)]),
+        Err(Ok(AccountError::FunctionNotAllowed))
+    );
+
+    let other_booking =
+        call(&s.vault.address, "tip", (s.account.address.clone(), 8_u64, 1_i128).into_val(env));
+    assert_eq!(check(vec![env, other_booking]), Err(Ok(AccountError::BookingNotAllowed)));
+
+    let reclaim = call(
+        &s.vault.address,
+        "reclaim_stale_session",
+        (s.account.address.clone(), 7_u64).into_val(env),
+    );
+    assert_eq!(check(vec![env, reclaim]), Err(Ok(AccountError::FunctionNotAllowed)));
+
+    let approve = call(
+        &s.token.address,
+        "approve",
+        (s.account.address.clone(), s.expert.clone(), 1_i128, 100_u32).into_val(env),
+    );
+    assert_eq!(check(vec![env, approve]), Err(Ok(AccountError::FunctionNotAllowed)));
+
+    let unknown = call(&s.expert, "tip", (s.account.address.clone(), 7_u64).into_val(env));
+    assert_eq!(check(vec![env, unknown]), Err(Ok(AccountError::FunctionNotAllowed)));
+
+    env.ledger().set_timestamp(1_001);
+    assert_eq!(check(vec![env, tip]), Err(Ok(AccountError::SessionExpired)));
+}
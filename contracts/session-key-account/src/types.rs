@@ -0,0 +1,27 @@
+use soroban_sdk::{contracttype, BytesN, Symbol, Vec};
+
+/// Signature passed to __check_auth: the signing key and its ed25519 signature of the payload
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountSignature {
+    pub public_key: BytesN<32>,     // Owner key or a registered session key
+    pub signature: BytesN<64>,      // ed25519 signature of the auth payload
+}
+
+/// Limits on what a session key may authorize
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionPolicy {
+    pub functions: Vec<Symbol>,     // Vault functions the key may call
+    pub booking_id: u64,            // The only booking the key may act on
+    pub spend_cap: i128,            // Maximum total of token transfers out of the account
+    pub expires_at: u64,            // Ledger timestamp after which the key is rejected
+}
+
+/// A registered session key and how much it has spent so far
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SessionState {
+    pub policy: SessionPolicy,      // Limits set by the owner
+    pub spent: i128,                // Token transfers authorized so far
+}